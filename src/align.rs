@@ -0,0 +1,103 @@
+/// Rounds `value` up to the nearest multiple of `alignment`.
+///
+/// Power of two alignments, e.g., Vulkan's buffer and memory alignments, are rounded with a mask,
+/// other alignments such as [`texel_copy_alignment`] are also supported.
+///
+/// # Panics
+/// * If `alignment` is zero.
+/// * If the rounded up value does not fit in a `u64`.
+pub fn align_up(value: u64, alignment: u64) -> u64 {
+    assert!(alignment > 0, "alignment must be greater than zero");
+
+    if !alignment.is_power_of_two() {
+        let Some(value) = value.checked_next_multiple_of(alignment) else {
+            panic!("aligning {value} up to {alignment} overflows");
+        };
+
+        return value;
+    }
+
+    let Some(value) = value.checked_add(alignment - 1) else {
+        panic!("aligning {value} up to {alignment} overflows");
+    };
+
+    value & !(alignment - 1)
+}
+
+/// Rounds `value` down to the nearest multiple of `alignment`.
+///
+/// `alignment` must be a power of two, this is checked in debug builds.
+pub fn align_down(value: u64, alignment: u64) -> u64 {
+    debug_assert!(
+        alignment.is_power_of_two(),
+        "alignment {alignment} is not a power of two"
+    );
+
+    value & !(alignment - 1)
+}
+
+/// Returns the alignment of a buffer offset used to copy to or from an image with texels of
+/// `texel_size` bytes, the least common multiple of the texel size and 4.
+pub fn texel_copy_alignment(texel_size: u64) -> u64 {
+    match texel_size % 4 {
+        0 => texel_size,
+        2 => texel_size * 2,
+        _ => texel_size * 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{align_down, align_up, texel_copy_alignment};
+
+    #[test]
+    fn align_up_rounds_to_multiple() {
+        assert_eq!(align_up(0, 16), 0);
+        assert_eq!(align_up(1, 16), 16);
+        assert_eq!(align_up(16, 16), 16);
+        assert_eq!(align_up(17, 16), 32);
+        assert_eq!(align_up(5, 1), 5);
+        assert_eq!(align_up(u64::MAX - 15, 16), u64::MAX - 15);
+    }
+
+    #[test]
+    fn align_up_rounds_to_non_power_of_two() {
+        assert_eq!(align_up(0, 12), 0);
+        assert_eq!(align_up(1, 12), 12);
+        assert_eq!(align_up(12, 12), 12);
+        assert_eq!(align_up(13, 12), 24);
+    }
+
+    #[test]
+    #[should_panic]
+    fn align_up_zero_alignment_panics() {
+        align_up(1, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn align_up_overflow_panics() {
+        align_up(u64::MAX - 14, 16);
+    }
+
+    #[test]
+    fn align_down_rounds_to_multiple() {
+        assert_eq!(align_down(0, 16), 0);
+        assert_eq!(align_down(15, 16), 0);
+        assert_eq!(align_down(16, 16), 16);
+        assert_eq!(align_down(31, 16), 16);
+        assert_eq!(align_down(u64::MAX, 16), u64::MAX - 15);
+    }
+
+    #[test]
+    fn texel_copy_alignment_is_multiple_of_texel_size_and_four() {
+        assert_eq!(texel_copy_alignment(1), 4);
+        assert_eq!(texel_copy_alignment(2), 4);
+        assert_eq!(texel_copy_alignment(3), 12);
+        assert_eq!(texel_copy_alignment(4), 4);
+        assert_eq!(texel_copy_alignment(6), 12);
+        assert_eq!(texel_copy_alignment(8), 8);
+        assert_eq!(texel_copy_alignment(12), 12);
+        assert_eq!(texel_copy_alignment(16), 16);
+    }
+}
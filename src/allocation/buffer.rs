@@ -1,8 +1,16 @@
+use core::{ffi::c_void, slice};
+
 use ash::vk;
 
-use crate::{VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, try_name};
+use crate::{
+    LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, align_down, align_up, try_name,
+};
 
-use super::{AllocationError, memory::allocate_buffer_memory};
+use super::{
+    AllocationError, find_memorytype_index,
+    memory::{allocate_buffer_memory, memory_type_flags},
+    sharing::sharing_mode,
+};
 
 /// Allocate and bind memory to a new buffer.
 pub unsafe fn allocate_buffer<Vulkan: VulkanContext>(
@@ -26,15 +34,293 @@ pub unsafe fn allocate_buffer<Vulkan: VulkanContext>(
 
     let (memory, requirements) = {
         let (memory, requirements) =
-            unsafe { allocate_buffer_memory(vulkan, buffer, memory_flags) }?;
+            match unsafe { allocate_buffer_memory(vulkan, buffer, memory_flags) } {
+                Ok(allocation) => allocation,
+                Err(error) => {
+                    unsafe {
+                        vulkan
+                            .device()
+                            .destroy_buffer(buffer, VK_GLOBAL_ALLOCATOR.as_deref())
+                    };
+
+                    return Err(error);
+                }
+            };
 
         unsafe { try_name(vulkan, memory, &format!("{label} Buffer Memory")) };
 
         (memory, requirements)
     };
 
-    unsafe { vulkan.device().bind_buffer_memory(buffer, memory, 0) }
-        .map_err(|e| VkError::new(e, "vkBindBufferMemory"))?;
+    if let Err(e) = unsafe { vulkan.device().bind_buffer_memory(buffer, memory, 0) } {
+        unsafe {
+            vulkan
+                .device()
+                .destroy_buffer(buffer, VK_GLOBAL_ALLOCATOR.as_deref());
+            vulkan
+                .device()
+                .free_memory(memory, VK_GLOBAL_ALLOCATOR.as_deref());
+        }
+
+        return Err(VkError::new(e, "vkBindBufferMemory").into());
+    }
 
     Ok((buffer, memory, requirements))
 }
+
+/// Allocate and bind memory to a new buffer like [`allocate_buffer`], but on
+/// `ERROR_OUT_OF_DEVICE_MEMORY` calls `on_oom` then tries again.
+///
+/// `on_oom` should free some cached or transient resources and return if the allocation should be
+/// retried.
+pub unsafe fn allocate_buffer_with_retry<Vulkan, OnOom>(
+    vulkan: &Vulkan,
+    create_info: &vk::BufferCreateInfo<'_>,
+    memory_flags: vk::MemoryPropertyFlags,
+    label: &str,
+    mut on_oom: OnOom,
+) -> Result<(vk::Buffer, vk::DeviceMemory, vk::MemoryRequirements), AllocationError>
+where
+    Vulkan: VulkanContext,
+    OnOom: FnMut() -> bool,
+{
+    loop {
+        match unsafe { allocate_buffer(vulkan, create_info, memory_flags, label) } {
+            Err(AllocationError::VkError(error))
+                if error.result() == vk::Result::ERROR_OUT_OF_DEVICE_MEMORY =>
+            {
+                if !on_oom() {
+                    return Err(AllocationError::VkError(error));
+                }
+            }
+
+            result => return result,
+        }
+    }
+}
+
+/// A host-visible buffer that is persistently mapped for its lifetime.
+pub struct MappedBuffer {
+    /// The buffer.
+    pub buffer: vk::Buffer,
+    /// The buffer's memory.
+    pub memory: vk::DeviceMemory,
+    /// The buffer's memory requirements.
+    pub requirements: vk::MemoryRequirements,
+    /// The size of the buffer in bytes.
+    pub size: u64,
+    /// The pointer to the start of the mapped memory.
+    pub pointer: *mut c_void,
+    /// If the memory is host-coherent, else writes must be flushed and reads invalidated.
+    pub is_coherent: bool,
+    /// The device's `nonCoherentAtomSize`, the alignment of flushed and invalidated ranges.
+    pub non_coherent_atom_size: u64,
+}
+
+impl MappedBuffer {
+    /// Returns the mapped memory as a mutable slice of `T`, any trailing bytes that do not fit a
+    /// whole `T` are excluded.
+    ///
+    /// # Safety
+    /// * The mapped memory **MUST** be valid for `T`.
+    /// * The GPU **MUST NOT** be accessing the memory while the slice is in use.
+    pub unsafe fn as_mut_slice<T: Copy>(&mut self) -> &mut [T] {
+        let count = self.size as usize / size_of::<T>();
+
+        unsafe { slice::from_raw_parts_mut(self.pointer.cast::<T>(), count) }
+    }
+
+    /// Makes host writes to the mapped memory available to the device, does nothing if the memory
+    /// is host-coherent.
+    pub unsafe fn flush<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) -> LabelledVkResult<()> {
+        unsafe { self.flush_range(vulkan, 0, self.size) }
+    }
+
+    /// Makes host writes to `size` bytes of the mapped memory at `offset` available to the device,
+    /// does nothing if the memory is host-coherent. The range is expanded to the
+    /// `nonCoherentAtomSize`.
+    pub unsafe fn flush_range<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        offset: u64,
+        size: u64,
+    ) -> LabelledVkResult<()> {
+        if self.is_coherent {
+            return Ok(());
+        }
+
+        let range = self.mapped_range(offset, size);
+
+        unsafe {
+            vulkan
+                .device()
+                .flush_mapped_memory_ranges(slice::from_ref(&range))
+        }
+        .map_err(|e| VkError::new(e, "vkFlushMappedMemoryRanges"))
+    }
+
+    /// Makes device writes to the memory visible to the host through the mapped memory, does
+    /// nothing if the memory is host-coherent.
+    pub unsafe fn invalidate<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+    ) -> LabelledVkResult<()> {
+        unsafe { self.invalidate_range(vulkan, 0, self.size) }
+    }
+
+    /// Makes device writes to `size` bytes of the memory at `offset` visible to the host through
+    /// the mapped memory, does nothing if the memory is host-coherent. The range is expanded to
+    /// the `nonCoherentAtomSize`.
+    pub unsafe fn invalidate_range<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        offset: u64,
+        size: u64,
+    ) -> LabelledVkResult<()> {
+        if self.is_coherent {
+            return Ok(());
+        }
+
+        let range = self.mapped_range(offset, size);
+
+        unsafe {
+            vulkan
+                .device()
+                .invalidate_mapped_memory_ranges(slice::from_ref(&range))
+        }
+        .map_err(|e| VkError::new(e, "vkInvalidateMappedMemoryRanges"))
+    }
+
+    /// Unmaps the memory and destroys the buffer.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
+        unsafe {
+            vulkan.device().unmap_memory(self.memory);
+            vulkan
+                .device()
+                .destroy_buffer(self.buffer, VK_GLOBAL_ALLOCATOR.as_deref());
+            vulkan
+                .device()
+                .free_memory(self.memory, VK_GLOBAL_ALLOCATOR.as_deref());
+        }
+    }
+
+    /// Returns the range of `size` bytes at `offset` expanded to the `nonCoherentAtomSize`, the
+    /// range extends to the end of the memory if the expanded range would pass it.
+    fn mapped_range(&self, offset: u64, size: u64) -> vk::MappedMemoryRange<'static> {
+        let start = align_down(offset, self.non_coherent_atom_size);
+        let end = align_up(offset + size, self.non_coherent_atom_size);
+
+        let size = if end >= self.requirements.size {
+            vk::WHOLE_SIZE
+        } else {
+            end - start
+        };
+
+        vk::MappedMemoryRange::default()
+            .memory(self.memory)
+            .offset(start)
+            .size(size)
+    }
+}
+
+/// Allocate and bind host-visible memory to a new buffer, then map the whole buffer for its
+/// lifetime.
+///
+/// If `prefer_coherent`, host-coherent memory is tried first before falling back to any
+/// host-visible memory. Use [`MappedBuffer::flush`] and [`MappedBuffer::invalidate`] to remain
+/// correct if the memory is not coherent.
+pub unsafe fn allocate_mapped_buffer<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    create_info: &vk::BufferCreateInfo<'_>,
+    prefer_coherent: bool,
+    label: &str,
+) -> Result<MappedBuffer, AllocationError> {
+    let coherent_flags =
+        vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT;
+
+    let coherent_allocation = if prefer_coherent {
+        match unsafe { allocate_buffer(vulkan, create_info, coherent_flags, label) } {
+            Err(AllocationError::NoSuitableMemoryType) => None,
+            result => Some(result?),
+        }
+    } else {
+        None
+    };
+
+    let (buffer, memory, requirements, is_coherent) = match coherent_allocation {
+        Some((buffer, memory, requirements)) => (buffer, memory, requirements, true),
+
+        None => {
+            let memory_flags = vk::MemoryPropertyFlags::HOST_VISIBLE;
+
+            let (buffer, memory, requirements) =
+                unsafe { allocate_buffer(vulkan, create_info, memory_flags, label) }?;
+
+            // The memory type chosen may still be coherent.
+            let is_coherent = find_memorytype_index(vulkan, requirements, memory_flags)
+                .is_some_and(|index| {
+                    memory_type_flags(vulkan, index)
+                        .contains(vk::MemoryPropertyFlags::HOST_COHERENT)
+                });
+
+            (buffer, memory, requirements, is_coherent)
+        }
+    };
+
+    let pointer = match unsafe {
+        vulkan
+            .device()
+            .map_memory(memory, 0, vk::WHOLE_SIZE, vk::MemoryMapFlags::empty())
+    } {
+        Ok(pointer) => pointer,
+        Err(e) => {
+            unsafe {
+                vulkan
+                    .device()
+                    .destroy_buffer(buffer, VK_GLOBAL_ALLOCATOR.as_deref());
+                vulkan
+                    .device()
+                    .free_memory(memory, VK_GLOBAL_ALLOCATOR.as_deref());
+            }
+
+            return Err(VkError::new(e, "vkMapMemory").into());
+        }
+    };
+
+    let non_coherent_atom_size = unsafe {
+        vulkan
+            .instance()
+            .get_physical_device_properties(vulkan.physical_device())
+    }
+    .limits
+    .non_coherent_atom_size;
+
+    Ok(MappedBuffer {
+        buffer,
+        memory,
+        requirements,
+        size: create_info.size,
+        pointer,
+        is_coherent,
+        non_coherent_atom_size,
+    })
+}
+
+/// Allocate and bind memory to a new buffer like [`allocate_buffer`], with the sharing mode and
+/// queue family indices set for use by `queue_families`. The buffer is `CONCURRENT` if more than
+/// one distinct family is given, else `EXCLUSIVE`.
+pub unsafe fn allocate_buffer_shared<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    create_info: &vk::BufferCreateInfo<'_>,
+    queue_families: &[u32],
+    memory_flags: vk::MemoryPropertyFlags,
+    label: &str,
+) -> Result<(vk::Buffer, vk::DeviceMemory, vk::MemoryRequirements), AllocationError> {
+    let (sharing_mode, queue_family_indices) = sharing_mode(queue_families);
+
+    let create_info = create_info
+        .sharing_mode(sharing_mode)
+        .queue_family_indices(&queue_family_indices);
+
+    unsafe { allocate_buffer(vulkan, &create_info, memory_flags, label) }
+}
@@ -1,10 +1,17 @@
+use core::slice;
+
 use ash::vk;
 
-use crate::{VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, try_name};
+use crate::{
+    MaybeMutex, Texture, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, cmd_transition_image,
+    commands::is_supported_layout, format_properties, onetime_command, try_name,
+};
 
-use super::{AllocationError, memory::allocate_image_memory};
+use super::{
+    AllocationError, allocate_mapped_buffer, memory::allocate_image_memory, sharing::sharing_mode,
+};
 
-/// Allocate and bind memory to a new buffer.
+/// Allocate and bind memory to a new image.
 pub unsafe fn allocate_image<Vulkan: VulkanContext>(
     vulkan: &Vulkan,
     create_info: &vk::ImageCreateInfo<'_>,
@@ -25,15 +32,328 @@ pub unsafe fn allocate_image<Vulkan: VulkanContext>(
     };
 
     let (memory, requirements) = {
-        let (memory, requirements) = unsafe { allocate_image_memory(vulkan, image, memory_flags) }?;
+        let (memory, requirements) =
+            match unsafe { allocate_image_memory(vulkan, image, memory_flags) } {
+                Ok(allocation) => allocation,
+                Err(error) => {
+                    unsafe {
+                        vulkan
+                            .device()
+                            .destroy_image(image, VK_GLOBAL_ALLOCATOR.as_deref())
+                    };
+
+                    return Err(error);
+                }
+            };
 
         unsafe { try_name(vulkan, memory, &format!("{label} Image Memory")) };
 
         (memory, requirements)
     };
 
-    unsafe { vulkan.device().bind_image_memory(image, memory, 0) }
-        .map_err(|e| VkError::new(e, "vkBindImageMemory"))?;
+    if let Err(e) = unsafe { vulkan.device().bind_image_memory(image, memory, 0) } {
+        unsafe {
+            vulkan
+                .device()
+                .destroy_image(image, VK_GLOBAL_ALLOCATOR.as_deref());
+            vulkan
+                .device()
+                .free_memory(memory, VK_GLOBAL_ALLOCATOR.as_deref());
+        }
+
+        return Err(VkError::new(e, "vkBindImageMemory").into());
+    }
 
     Ok((image, memory, requirements))
 }
+
+/// Allocate and bind memory to a new image like [`allocate_image`], with the sharing mode and
+/// queue family indices set for use by `queue_families`. The image is `CONCURRENT` if more than
+/// one distinct family is given, else `EXCLUSIVE`.
+pub unsafe fn allocate_image_shared<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    create_info: &vk::ImageCreateInfo<'_>,
+    queue_families: &[u32],
+    memory_flags: vk::MemoryPropertyFlags,
+    label: &str,
+) -> Result<(vk::Image, vk::DeviceMemory, vk::MemoryRequirements), AllocationError> {
+    let (sharing_mode, queue_family_indices) = sharing_mode(queue_families);
+
+    let create_info = create_info
+        .sharing_mode(sharing_mode)
+        .queue_family_indices(&queue_family_indices);
+
+    unsafe { allocate_image(vulkan, &create_info, memory_flags, label) }
+}
+
+/// Creates a device-local 2D image with `STORAGE | SAMPLED` usage and a view of it, for compute
+/// shader output.
+///
+/// # Safety
+/// * The image is left in `vk::ImageLayout::UNDEFINED` and **MUST** be transitioned to
+///   `vk::ImageLayout::GENERAL` before being written to.
+pub unsafe fn allocate_storage_image<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    extent: vk::Extent2D,
+    format: vk::Format,
+    label: &str,
+) -> Result<Texture, AllocationError> {
+    // Validate format support
+    {
+        let features =
+            vk::FormatFeatureFlags::STORAGE_IMAGE | vk::FormatFeatureFlags::SAMPLED_IMAGE;
+
+        if !format_properties(vulkan, format)
+            .optimal_tiling_features
+            .contains(features)
+        {
+            return Err(AllocationError::UnsupportedFormatFeatures { format, features });
+        }
+    }
+
+    let (image, memory, _) = {
+        let create_info = vk::ImageCreateInfo::default()
+            .image_type(vk::ImageType::TYPE_2D)
+            .format(format)
+            .extent(vk::Extent3D {
+                width: extent.width,
+                height: extent.height,
+                depth: 1,
+            })
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(vk::SampleCountFlags::TYPE_1)
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(vk::ImageUsageFlags::STORAGE | vk::ImageUsageFlags::SAMPLED)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+
+        unsafe {
+            allocate_image(
+                vulkan,
+                &create_info,
+                vk::MemoryPropertyFlags::DEVICE_LOCAL,
+                label,
+            )
+        }?
+    };
+
+    let view = {
+        let create_info = vk::ImageViewCreateInfo::default()
+            .image(image)
+            .view_type(vk::ImageViewType::TYPE_2D)
+            .format(format)
+            .subresource_range(
+                vk::ImageSubresourceRange::default()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .base_mip_level(0)
+                    .level_count(1)
+                    .base_array_layer(0)
+                    .layer_count(1),
+            );
+
+        let view = match unsafe {
+            vulkan
+                .device()
+                .create_image_view(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+        } {
+            Ok(view) => view,
+            Err(e) => {
+                unsafe {
+                    vulkan
+                        .device()
+                        .destroy_image(image, VK_GLOBAL_ALLOCATOR.as_deref());
+                    vulkan
+                        .device()
+                        .free_memory(memory, VK_GLOBAL_ALLOCATOR.as_deref());
+                }
+
+                return Err(VkError::new(e, "vkCreateImageView").into());
+            }
+        };
+
+        unsafe { try_name(vulkan, view, &format!("{label} Image View")) };
+
+        view
+    };
+
+    Ok(Texture {
+        image,
+        memory,
+        view,
+    })
+}
+
+/// Creates a device-local image and uploads `pixels` to it through a staging buffer, leaving the
+/// image in `final_layout`.
+///
+/// `vk::ImageUsageFlags::TRANSFER_DST` is added to the create info's usage. Only mip level `0` and
+/// array layer `0` are uploaded to and transitioned.
+///
+/// # Panics
+/// * If `final_layout` is not supported by [`cmd_transition_image`].
+///
+/// # Safety
+/// * `create_info.initial_layout` **MUST** be `vk::ImageLayout::UNDEFINED`.
+/// * `pixels` **MUST** be tightly packed data for the image's format and extent.
+pub unsafe fn upload_image<'m, Vulkan, Queue, Pool>(
+    vulkan: &Vulkan,
+    command_pool: Pool,
+    queue: Queue,
+    create_info: &vk::ImageCreateInfo<'_>,
+    pixels: &[u8],
+    final_layout: vk::ImageLayout,
+    label: &str,
+) -> Result<(vk::Image, vk::DeviceMemory, vk::MemoryRequirements), AllocationError>
+where
+    Vulkan: VulkanContext,
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    Pool: Into<MaybeMutex<'m, vk::CommandPool>>,
+{
+    assert!(
+        is_supported_layout(final_layout),
+        "Unsupported final layout {final_layout:?}"
+    );
+
+    let create_info = create_info.usage(create_info.usage | vk::ImageUsageFlags::TRANSFER_DST);
+
+    let (image, memory, requirements) = unsafe {
+        allocate_image(
+            vulkan,
+            &create_info,
+            vk::MemoryPropertyFlags::DEVICE_LOCAL,
+            label,
+        )
+    }?;
+
+    let destroy_image = || unsafe {
+        vulkan
+            .device()
+            .destroy_image(image, VK_GLOBAL_ALLOCATOR.as_deref());
+        vulkan
+            .device()
+            .free_memory(memory, VK_GLOBAL_ALLOCATOR.as_deref());
+    };
+
+    if let Err(error) = unsafe {
+        upload_to_image(
+            vulkan,
+            command_pool,
+            queue,
+            image,
+            create_info.extent,
+            pixels,
+            final_layout,
+            label,
+        )
+    } {
+        destroy_image();
+        return Err(error);
+    }
+
+    Ok((image, memory, requirements))
+}
+
+/// Uploads `pixels` to an existing image through a staging buffer, transitioning it from
+/// `UNDEFINED` to `TRANSFER_DST_OPTIMAL` for the copy then to `final_layout`, all in a single
+/// onetime command. Any previous contents of the image are discarded.
+///
+/// Only mip level `0` and array layer `0` are uploaded to and transitioned.
+///
+/// # Panics
+/// * If `final_layout` is not supported by [`cmd_transition_image`].
+///
+/// # Safety
+/// * `image` **MUST** have been created with `vk::ImageUsageFlags::TRANSFER_DST`, a colour
+///   format, and `extent`.
+/// * `image` **MUST NOT** be in use.
+/// * `pixels` **MUST** be tightly packed data for the image's format and `extent`.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn upload_to_image<'m, Vulkan, Queue, Pool>(
+    vulkan: &Vulkan,
+    command_pool: Pool,
+    queue: Queue,
+    image: vk::Image,
+    extent: vk::Extent3D,
+    pixels: &[u8],
+    final_layout: vk::ImageLayout,
+    label: &str,
+) -> Result<(), AllocationError>
+where
+    Vulkan: VulkanContext,
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    Pool: Into<MaybeMutex<'m, vk::CommandPool>>,
+{
+    assert!(
+        is_supported_layout(final_layout),
+        "Unsupported final layout {final_layout:?}"
+    );
+
+    // Create the staging buffer
+    let mut staging = {
+        let create_info = vk::BufferCreateInfo::default()
+            .size(pixels.len() as u64)
+            .usage(vk::BufferUsageFlags::TRANSFER_SRC)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+
+        unsafe { allocate_mapped_buffer(vulkan, &create_info, true, &format!("{label} Staging")) }?
+    };
+
+    unsafe { staging.as_mut_slice::<u8>()[..pixels.len()].copy_from_slice(pixels) };
+
+    if let Err(error) = unsafe { staging.flush(vulkan) } {
+        unsafe { staging.destroy(vulkan) };
+        return Err(error.into());
+    }
+
+    // Upload
+    let upload_result = {
+        let region = vk::BufferImageCopy::default()
+            .buffer_offset(0)
+            .buffer_row_length(0)
+            .buffer_image_height(0)
+            .image_subresource(
+                vk::ImageSubresourceLayers::default()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .mip_level(0)
+                    .base_array_layer(0)
+                    .layer_count(1),
+            )
+            .image_offset(vk::Offset3D::default())
+            .image_extent(extent);
+
+        let cmd_fn = |vulkan: &Vulkan, command_buffer| unsafe {
+            cmd_transition_image(
+                vulkan,
+                command_buffer,
+                image,
+                vk::ImageLayout::UNDEFINED,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            );
+
+            vulkan.device().cmd_copy_buffer_to_image(
+                command_buffer,
+                staging.buffer,
+                image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                slice::from_ref(&region),
+            );
+
+            cmd_transition_image(
+                vulkan,
+                command_buffer,
+                image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                final_layout,
+            );
+        };
+
+        unsafe { onetime_command(vulkan, command_pool, queue, cmd_fn, label) }
+    };
+
+    unsafe { staging.destroy(vulkan) };
+
+    upload_result?;
+
+    Ok(())
+}
@@ -11,23 +11,17 @@ pub unsafe fn allocate_buffer_memory<Vulkan: VulkanContext>(
     memory_flags: vk::MemoryPropertyFlags,
 ) -> Result<(vk::DeviceMemory, vk::MemoryRequirements), AllocationError> {
     // Get the memory requirements
-    let (memory_requirements, should_be_dedicated) = {
-        let buffer_requirements = vk::BufferMemoryRequirementsInfo2::default().buffer(buffer);
-        let mut dedicated_requirements = vk::MemoryDedicatedRequirements::default();
-        let mut memory_requirements =
-            vk::MemoryRequirements2::default().push_next(&mut dedicated_requirements);
-
-        unsafe {
-            vulkan
-                .device()
-                .get_buffer_memory_requirements2(&buffer_requirements, &mut memory_requirements)
-        };
-
-        let memory_requirements = memory_requirements.memory_requirements;
-        let should_be_dedicated = dedicated_requirements.prefers_dedicated_allocation == vk::TRUE;
-
-        (memory_requirements, should_be_dedicated)
-    };
+    let (memory_requirements, should_be_dedicated) =
+        unsafe { buffer_memory_requirements(vulkan, buffer) };
+
+    // Check the allocation size
+    let max_allocation_size = max_memory_allocation_size(vulkan);
+    if memory_requirements.size > max_allocation_size {
+        return Err(AllocationError::ExceedsMaxAllocationSize {
+            size: memory_requirements.size,
+            max_allocation_size,
+        });
+    }
 
     // Find the memory index
     let memory_index = find_memorytype_index(vulkan, memory_requirements, memory_flags)
@@ -65,23 +59,17 @@ pub unsafe fn allocate_image_memory<Vulkan: VulkanContext>(
     memory_flags: vk::MemoryPropertyFlags,
 ) -> Result<(vk::DeviceMemory, vk::MemoryRequirements), AllocationError> {
     // Get the memory requirements
-    let (memory_requirements, should_be_dedicated) = {
-        let image_requirements = vk::ImageMemoryRequirementsInfo2::default().image(image);
-        let mut dedicated_requirements = vk::MemoryDedicatedRequirements::default();
-        let mut memory_requirements =
-            vk::MemoryRequirements2::default().push_next(&mut dedicated_requirements);
-
-        unsafe {
-            vulkan
-                .device()
-                .get_image_memory_requirements2(&image_requirements, &mut memory_requirements)
-        };
-
-        let memory_requirements = memory_requirements.memory_requirements;
-        let should_be_dedicated = dedicated_requirements.prefers_dedicated_allocation == vk::TRUE;
-
-        (memory_requirements, should_be_dedicated)
-    };
+    let (memory_requirements, should_be_dedicated) =
+        unsafe { image_memory_requirements(vulkan, image) };
+
+    // Check the allocation size
+    let max_allocation_size = max_memory_allocation_size(vulkan);
+    if memory_requirements.size > max_allocation_size {
+        return Err(AllocationError::ExceedsMaxAllocationSize {
+            size: memory_requirements.size,
+            max_allocation_size,
+        });
+    }
 
     // Find the memory index
     let memory_index = find_memorytype_index(vulkan, memory_requirements, memory_flags)
@@ -112,6 +100,96 @@ pub unsafe fn allocate_image_memory<Vulkan: VulkanContext>(
     Ok((memory, memory_requirements))
 }
 
+/// Returns if the device prefers `buffer` to have a dedicated allocation, useful for routing the
+/// buffer around a sub-allocator.
+pub unsafe fn should_dedicate_buffer<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    buffer: vk::Buffer,
+) -> bool {
+    unsafe { buffer_memory_requirements(vulkan, buffer) }.1
+}
+
+/// Returns if the device prefers `image` to have a dedicated allocation, useful for routing the
+/// image around a sub-allocator.
+pub unsafe fn should_dedicate_image<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    image: vk::Image,
+) -> bool {
+    unsafe { image_memory_requirements(vulkan, image) }.1
+}
+
+/// Returns the memory requirements of `buffer` and if it should be dedicated.
+unsafe fn buffer_memory_requirements<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    buffer: vk::Buffer,
+) -> (vk::MemoryRequirements, bool) {
+    let buffer_requirements = vk::BufferMemoryRequirementsInfo2::default().buffer(buffer);
+    let mut dedicated_requirements = vk::MemoryDedicatedRequirements::default();
+    let mut memory_requirements =
+        vk::MemoryRequirements2::default().push_next(&mut dedicated_requirements);
+
+    unsafe {
+        vulkan
+            .device()
+            .get_buffer_memory_requirements2(&buffer_requirements, &mut memory_requirements)
+    };
+
+    let memory_requirements = memory_requirements.memory_requirements;
+    let should_be_dedicated = dedicated_requirements.prefers_dedicated_allocation == vk::TRUE;
+
+    (memory_requirements, should_be_dedicated)
+}
+
+/// Returns the memory requirements of `image` and if it should be dedicated.
+unsafe fn image_memory_requirements<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    image: vk::Image,
+) -> (vk::MemoryRequirements, bool) {
+    let image_requirements = vk::ImageMemoryRequirementsInfo2::default().image(image);
+    let mut dedicated_requirements = vk::MemoryDedicatedRequirements::default();
+    let mut memory_requirements =
+        vk::MemoryRequirements2::default().push_next(&mut dedicated_requirements);
+
+    unsafe {
+        vulkan
+            .device()
+            .get_image_memory_requirements2(&image_requirements, &mut memory_requirements)
+    };
+
+    let memory_requirements = memory_requirements.memory_requirements;
+    let should_be_dedicated = dedicated_requirements.prefers_dedicated_allocation == vk::TRUE;
+
+    (memory_requirements, should_be_dedicated)
+}
+
+/// Returns the device's `maxMemoryAllocationSize`, the largest size of a single allocation.
+pub fn max_memory_allocation_size<Vulkan: VulkanContext>(vulkan: &Vulkan) -> u64 {
+    let mut maintenance_3 = vk::PhysicalDeviceMaintenance3Properties::default();
+    let mut properties = vk::PhysicalDeviceProperties2::default().push_next(&mut maintenance_3);
+
+    unsafe {
+        vulkan
+            .instance()
+            .get_physical_device_properties2(vulkan.physical_device(), &mut properties)
+    };
+
+    maintenance_3.max_memory_allocation_size
+}
+
+/// Returns the property flags of the memory type at `memory_type_index`.
+pub(super) fn memory_type_flags<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    memory_type_index: u32,
+) -> vk::MemoryPropertyFlags {
+    let memory_properties = unsafe {
+        vulkan
+            .instance()
+            .get_physical_device_memory_properties(vulkan.physical_device())
+    };
+
+    memory_properties.memory_types[memory_type_index as usize].property_flags
+}
+
 /// Finds suitable memory type index for given requirements.
 pub fn find_memorytype_index<Vulkan: VulkanContext>(
     vulkan: &Vulkan,
@@ -1,16 +1,37 @@
-pub use buffer::allocate_buffer;
-pub use image::allocate_image;
-pub use memory::{allocate_buffer_memory, allocate_image_memory, find_memorytype_index};
+pub use buffer::{
+    MappedBuffer, allocate_buffer, allocate_buffer_shared, allocate_buffer_with_retry,
+    allocate_mapped_buffer,
+};
+pub use image::{
+    allocate_image, allocate_image_shared, allocate_storage_image, upload_image, upload_to_image,
+};
+pub use memory::{
+    allocate_buffer_memory, allocate_image_memory, find_memorytype_index,
+    max_memory_allocation_size, should_dedicate_buffer, should_dedicate_image,
+};
+pub(crate) use readback::read_with;
+pub use readback::{read_buffer_into, read_buffer_region};
+pub use scalar_readback::ScalarReadback;
 pub use slice::{BufferAlignment, BufferUsageFlags};
+pub use sparse::{create_sparse_buffer, queue_bind_sparse_buffer};
+pub use staging::{StagingAllocation, StagingGuard, StagingRing, StagingUploader};
+pub use upload::chunked_upload;
 pub use vk_global_allocator::VK_GLOBAL_ALLOCATOR;
 
 use crate::VkError;
+use ash::vk;
 use thiserror::Error;
 
 mod buffer;
 mod image;
 mod memory;
+mod readback;
+mod scalar_readback;
+mod sharing;
 mod slice;
+mod sparse;
+mod staging;
+mod upload;
 /// Utilities for using the Rust global allocator with Vulkan.
 pub mod vk_global_allocator;
 
@@ -24,4 +45,25 @@ pub enum AllocationError {
     /// The allocation failed because the device didn't have a memory type to match the allocation.
     #[error("The device had no suitable memory type for the allocation")]
     NoSuitableMemoryType,
+
+    /// The allocation failed because it is larger than the device's `maxMemoryAllocationSize`.
+    #[error(
+        "The allocation of {size} bytes exceeds the device's maximum allocation size of {max_allocation_size} bytes"
+    )]
+    ExceedsMaxAllocationSize {
+        /// The size of the requested allocation.
+        size: u64,
+        /// The device's maximum allocation size.
+        max_allocation_size: u64,
+    },
+
+    /// The allocation failed because the device doesn't support the required features for the
+    /// format.
+    #[error("The format {format:?} does not support {features:?}")]
+    UnsupportedFormatFeatures {
+        /// The format of the allocation.
+        format: vk::Format,
+        /// The required format features.
+        features: vk::FormatFeatureFlags,
+    },
 }
@@ -0,0 +1,210 @@
+use core::slice;
+
+use ash::vk;
+
+use crate::{LabelledVkResult, MaybeMutex, VulkanContext, onetime_command};
+
+use super::{AllocationError, MappedBuffer, allocate_mapped_buffer};
+
+/// Reads `count` values of `T` from `src_buffer` starting at `offset` by copying them into a
+/// temporary host-visible staging buffer.
+///
+/// # Safety
+/// * `src_buffer` **MUST** have been created with `vk::BufferUsageFlags::TRANSFER_SRC`.
+/// * The region **MUST** be within `src_buffer` and contain valid values of `T`.
+/// * Any writes to the region **MUST** have completed before this is called.
+pub unsafe fn read_buffer_region<'m, T, Vulkan, Queue, Pool>(
+    vulkan: &Vulkan,
+    command_pool: Pool,
+    queue: Queue,
+    src_buffer: vk::Buffer,
+    offset: u64,
+    count: usize,
+    label: &str,
+) -> Result<Vec<T>, AllocationError>
+where
+    T: Copy,
+    Vulkan: VulkanContext,
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    Pool: Into<MaybeMutex<'m, vk::CommandPool>>,
+{
+    let copy_fn = buffer_copy_fn::<T, Vulkan>(src_buffer, offset, count);
+    let read_fn = |values: &[T]| values.to_vec();
+
+    unsafe {
+        read_with(
+            vulkan,
+            (command_pool.into(), queue.into()),
+            count,
+            copy_fn,
+            read_fn,
+            label,
+        )
+    }
+}
+
+/// Reads values of `T` from `src_buffer` starting at `offset` into `output` like
+/// [`read_buffer_region`], reading `output.len()` values without allocating the output.
+///
+/// # Safety
+/// * `src_buffer` **MUST** have been created with `vk::BufferUsageFlags::TRANSFER_SRC`.
+/// * The region **MUST** be within `src_buffer` and contain valid values of `T`.
+/// * Any writes to the region **MUST** have completed before this is called.
+pub unsafe fn read_buffer_into<'m, T, Vulkan, Queue, Pool>(
+    vulkan: &Vulkan,
+    command_pool: Pool,
+    queue: Queue,
+    src_buffer: vk::Buffer,
+    offset: u64,
+    output: &mut [T],
+    label: &str,
+) -> Result<(), AllocationError>
+where
+    T: Copy,
+    Vulkan: VulkanContext,
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    Pool: Into<MaybeMutex<'m, vk::CommandPool>>,
+{
+    let count = output.len();
+    let copy_fn = buffer_copy_fn::<T, Vulkan>(src_buffer, offset, count);
+    let read_fn = |values: &[T]| output.copy_from_slice(values);
+
+    unsafe {
+        read_with(
+            vulkan,
+            (command_pool.into(), queue.into()),
+            count,
+            copy_fn,
+            read_fn,
+            label,
+        )
+    }
+}
+
+/// Returns a function recording a copy of `count` values of `T` from `src_buffer` at `offset` to
+/// the start of a staging buffer.
+fn buffer_copy_fn<T, Vulkan: VulkanContext>(
+    src_buffer: vk::Buffer,
+    offset: u64,
+    count: usize,
+) -> impl FnOnce(&Vulkan, vk::CommandBuffer, vk::Buffer) {
+    let region = vk::BufferCopy::default()
+        .src_offset(offset)
+        .dst_offset(0)
+        .size((count * size_of::<T>()) as u64);
+
+    move |vulkan: &Vulkan, command_buffer, staging_buffer| unsafe {
+        vulkan.device().cmd_copy_buffer(
+            command_buffer,
+            src_buffer,
+            staging_buffer,
+            slice::from_ref(&region),
+        )
+    }
+}
+
+/// Creates a temporary staging buffer for `count` values, records `copy_fn` to copy the values
+/// into it, waits for completion, then calls `read_fn` with the values.
+pub(crate) unsafe fn read_with<T, R, Vulkan, CopyFn, ReadFn>(
+    vulkan: &Vulkan,
+    (command_pool, queue): (MaybeMutex<'_, vk::CommandPool>, MaybeMutex<'_, vk::Queue>),
+    count: usize,
+    copy_fn: CopyFn,
+    read_fn: ReadFn,
+    label: &str,
+) -> Result<R, AllocationError>
+where
+    T: Copy,
+    Vulkan: VulkanContext,
+    CopyFn: FnOnce(&Vulkan, vk::CommandBuffer, vk::Buffer),
+    ReadFn: FnOnce(&[T]) -> R,
+{
+    if count == 0 {
+        return Ok(read_fn(&[]));
+    }
+
+    let size = (count * size_of::<T>()) as u64;
+
+    // Create the staging buffer
+    let staging = {
+        let create_info = vk::BufferCreateInfo::default()
+            .size(size)
+            .usage(vk::BufferUsageFlags::TRANSFER_DST)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+
+        unsafe { allocate_mapped_buffer(vulkan, &create_info, true, &format!("{label} Readback")) }?
+    };
+
+    // Copy the values then read them
+    let read_result = unsafe {
+        copy_and_read(
+            vulkan,
+            (command_pool, queue),
+            &staging,
+            count,
+            copy_fn,
+            read_fn,
+            label,
+        )
+    };
+
+    // Cleanup
+    unsafe { staging.destroy(vulkan) };
+
+    let output = read_result?;
+
+    Ok(output)
+}
+
+/// Records `copy_fn` to copy the values into the staging buffer, waits for completion, then reads
+/// the values.
+unsafe fn copy_and_read<T, R, Vulkan, CopyFn, ReadFn>(
+    vulkan: &Vulkan,
+    (command_pool, queue): (MaybeMutex<'_, vk::CommandPool>, MaybeMutex<'_, vk::Queue>),
+    staging: &MappedBuffer,
+    count: usize,
+    copy_fn: CopyFn,
+    read_fn: ReadFn,
+    label: &str,
+) -> LabelledVkResult<R>
+where
+    T: Copy,
+    Vulkan: VulkanContext,
+    CopyFn: FnOnce(&Vulkan, vk::CommandBuffer, vk::Buffer),
+    ReadFn: FnOnce(&[T]) -> R,
+{
+    // Copy the values and make them visible to the host
+    {
+        let barrier = vk::BufferMemoryBarrier::default()
+            .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+            .dst_access_mask(vk::AccessFlags::HOST_READ)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .buffer(staging.buffer)
+            .offset(0)
+            .size(vk::WHOLE_SIZE);
+
+        let cmd_fn = |vulkan: &Vulkan, command_buffer| unsafe {
+            copy_fn(vulkan, command_buffer, staging.buffer);
+
+            vulkan.device().cmd_pipeline_barrier(
+                command_buffer,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::PipelineStageFlags::HOST,
+                vk::DependencyFlags::empty(),
+                &[],
+                slice::from_ref(&barrier),
+                &[],
+            );
+        };
+
+        unsafe { onetime_command(vulkan, command_pool, queue, cmd_fn, label) }?;
+    }
+
+    // Read the values
+    unsafe { staging.invalidate(vulkan) }?;
+
+    let output = read_fn(unsafe { slice::from_raw_parts(staging.pointer.cast::<T>(), count) });
+
+    Ok(output)
+}
@@ -0,0 +1,103 @@
+use core::{marker::PhantomData, slice};
+
+use ash::vk;
+
+use crate::{LabelledVkResult, VulkanContext};
+
+use super::{AllocationError, MappedBuffer, allocate_mapped_buffer};
+
+/// A persistently mapped host-visible buffer holding a single `T`, for repeatedly reading a small
+/// result back from the GPU without allocating or mapping per read.
+pub struct ScalarReadback<T: Copy> {
+    /// The mapped buffer the value is copied into.
+    pub buffer: MappedBuffer,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Copy> ScalarReadback<T> {
+    /// Creates a new readback buffer large enough to hold a `T`.
+    pub unsafe fn new<Vulkan: VulkanContext>(
+        vulkan: &Vulkan,
+        label: &str,
+    ) -> Result<Self, AllocationError> {
+        let create_info = vk::BufferCreateInfo::default()
+            .size(size_of::<T>() as u64)
+            .usage(vk::BufferUsageFlags::TRANSFER_DST)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+
+        let buffer = unsafe {
+            allocate_mapped_buffer(vulkan, &create_info, true, &format!("{label} Readback"))
+        }?;
+
+        Ok(Self {
+            buffer,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Records copying the `T` in `src_buffer` at `src_offset` into the readback buffer and making
+    /// it visible to the host.
+    ///
+    /// # Safety
+    /// * `src_buffer` **MUST** have been created with `vk::BufferUsageFlags::TRANSFER_SRC`.
+    /// * Any writes to the region **MUST** be made available to the transfer stage before this is
+    ///   executed.
+    pub unsafe fn cmd_copy_scalar<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+        src_buffer: vk::Buffer,
+        src_offset: u64,
+    ) {
+        let region = vk::BufferCopy::default()
+            .src_offset(src_offset)
+            .dst_offset(0)
+            .size(size_of::<T>() as u64);
+
+        let barrier = vk::BufferMemoryBarrier::default()
+            .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+            .dst_access_mask(vk::AccessFlags::HOST_READ)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .buffer(self.buffer.buffer)
+            .offset(0)
+            .size(vk::WHOLE_SIZE);
+
+        unsafe {
+            vulkan.device().cmd_copy_buffer(
+                command_buffer,
+                src_buffer,
+                self.buffer.buffer,
+                slice::from_ref(&region),
+            );
+
+            vulkan.device().cmd_pipeline_barrier(
+                command_buffer,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::PipelineStageFlags::HOST,
+                vk::DependencyFlags::empty(),
+                &[],
+                slice::from_ref(&barrier),
+                &[],
+            );
+        }
+    }
+
+    /// Reads the value copied by [`Self::cmd_copy_scalar`].
+    ///
+    /// # Safety
+    /// * The submission containing the copy **MUST** have completed.
+    /// * The copied bytes **MUST** be a valid `T`.
+    pub unsafe fn read<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) -> LabelledVkResult<T> {
+        unsafe { self.buffer.invalidate(vulkan) }?;
+
+        let value = unsafe { self.buffer.pointer.cast::<T>().read_unaligned() };
+
+        Ok(value)
+    }
+
+    /// Destroys the readback buffer.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
+        unsafe { self.buffer.destroy(vulkan) };
+    }
+}
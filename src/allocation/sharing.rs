@@ -0,0 +1,16 @@
+use ash::vk;
+
+/// Returns the sharing mode and queue family indices for a resource used by `queue_families`.
+/// `CONCURRENT` with the distinct families if more than one distinct family is given, else
+/// `EXCLUSIVE` with no families.
+pub(super) fn sharing_mode(queue_families: &[u32]) -> (vk::SharingMode, Vec<u32>) {
+    let mut distinct_families = queue_families.to_vec();
+    distinct_families.sort_unstable();
+    distinct_families.dedup();
+
+    if distinct_families.len() > 1 {
+        (vk::SharingMode::CONCURRENT, distinct_families)
+    } else {
+        (vk::SharingMode::EXCLUSIVE, vec![])
+    }
+}
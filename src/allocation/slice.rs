@@ -2,7 +2,7 @@
 
 use ash::vk;
 
-use crate::VulkanContext;
+use crate::{VulkanContext, align_up};
 
 #[repr(transparent)]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -93,10 +93,7 @@ impl BufferAlignment {
                 .max(1)
         };
 
-        let start_padding = (minimum_offset_alignment - previous_end % minimum_offset_alignment)
-            % minimum_offset_alignment;
-
-        let offset = previous_end + start_padding;
+        let offset = align_up(previous_end, minimum_offset_alignment);
 
         let element_padding = (element_alignment - offset % element_alignment) % element_alignment;
         let element_size = element_size + element_padding;
@@ -0,0 +1,75 @@
+use core::slice;
+
+use ash::vk;
+
+use crate::{LabelledVkResult, MaybeMutex, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, try_name};
+
+/// Creates a sparse, partially resident buffer without binding any memory. The returned memory
+/// requirements' alignment is the size of a sparse page.
+///
+/// `vk::BufferCreateFlags::SPARSE_BINDING` and `vk::BufferCreateFlags::SPARSE_RESIDENCY` are added
+/// to the create info's flags.
+///
+/// # Safety
+/// * The device **MUST** have been created with the features enabled by
+///   [`crate::requirements::SparseBinding`].
+pub unsafe fn create_sparse_buffer<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    create_info: &vk::BufferCreateInfo<'_>,
+    label: &str,
+) -> LabelledVkResult<(vk::Buffer, vk::MemoryRequirements)> {
+    let create_info = create_info.flags(
+        create_info.flags
+            | vk::BufferCreateFlags::SPARSE_BINDING
+            | vk::BufferCreateFlags::SPARSE_RESIDENCY,
+    );
+
+    let buffer = unsafe {
+        vulkan
+            .device()
+            .create_buffer(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+    }
+    .map_err(|e| VkError::new(e, "vkCreateBuffer"))?;
+
+    unsafe { try_name(vulkan, buffer, &format!("{label} Sparse Buffer")) };
+
+    let requirements = unsafe { vulkan.device().get_buffer_memory_requirements(buffer) };
+
+    Ok((buffer, requirements))
+}
+
+/// Binds (or unbinds, with a null memory) memory pages to a sparse buffer. `fence` is signalled
+/// once the binding has completed.
+///
+/// # Safety
+/// * `queue` **MUST** support `vk::QueueFlags::SPARSE_BINDING`.
+/// * Each bind's `resource_offset` and `size` **MUST** be multiples of the buffer's sparse page
+///   size.
+pub unsafe fn queue_bind_sparse_buffer<'m, Vulkan, Queue>(
+    vulkan: &Vulkan,
+    queue: Queue,
+    buffer: vk::Buffer,
+    binds: &[vk::SparseMemoryBind],
+    fence: vk::Fence,
+) -> LabelledVkResult<()>
+where
+    Vulkan: VulkanContext,
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+{
+    let buffer_bind_info = vk::SparseBufferMemoryBindInfo::default()
+        .buffer(buffer)
+        .binds(binds);
+
+    let bind_info = vk::BindSparseInfo::default().buffer_binds(slice::from_ref(&buffer_bind_info));
+
+    let (queue, _queue_guard) = queue.into().lock();
+
+    unsafe {
+        vulkan
+            .device()
+            .queue_bind_sparse(queue, slice::from_ref(&bind_info), fence)
+    }
+    .map_err(|e| VkError::new(e, "vkQueueBindSparse"))?;
+
+    Ok(())
+}
@@ -0,0 +1,558 @@
+use core::{mem::MaybeUninit, slice};
+
+use ash::vk;
+
+use crate::{
+    LabelledVkResult, MaybeMutex, VkError, VulkanContext, align_up, onetime_command,
+    texel_copy_alignment,
+};
+
+use super::{
+    AllocationError, BufferAlignment, BufferUsageFlags, MappedBuffer, allocate_mapped_buffer,
+};
+
+/// The alignment of each buffer upload within the staging buffer. Image uploads are instead
+/// aligned for their texel size, see [`texel_copy_alignment`].
+const STAGING_ALIGNMENT: u64 = 16;
+
+/// A copy from the staging buffer that has been queued but not submitted.
+#[derive(Debug, Clone, Copy)]
+enum PendingCopy {
+    Buffer {
+        dst_buffer: vk::Buffer,
+        region: vk::BufferCopy,
+    },
+    Image {
+        dst_image: vk::Image,
+        region: vk::BufferImageCopy,
+    },
+}
+
+/// Uploads data to buffers and images through a reusable persistently mapped staging buffer.
+/// Uploads are queued into the staging buffer, then copied in a single submission by
+/// [`StagingUploader::submit`].
+pub struct StagingUploader {
+    /// The staging buffer.
+    staging: MappedBuffer,
+    /// The offset in the staging buffer of the next upload.
+    head: u64,
+    pending_copies: Vec<PendingCopy>,
+}
+
+impl StagingUploader {
+    /// Creates a new uploader with a staging buffer of `capacity` bytes.
+    pub unsafe fn new<Vulkan: VulkanContext>(
+        vulkan: &Vulkan,
+        capacity: u64,
+        label: &str,
+    ) -> Result<Self, AllocationError> {
+        let create_info = vk::BufferCreateInfo::default()
+            .size(capacity)
+            .usage(vk::BufferUsageFlags::TRANSFER_SRC)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+
+        let staging = unsafe {
+            allocate_mapped_buffer(vulkan, &create_info, true, &format!("{label} Staging"))
+        }?;
+
+        Ok(Self {
+            staging,
+            head: 0,
+            pending_copies: vec![],
+        })
+    }
+
+    /// Queues uploading `data` to `dst_buffer` at `dst_offset`. Returns `false` if the staging
+    /// buffer does not have enough space, the pending uploads should be submitted then this retried.
+    ///
+    /// # Safety
+    /// * `dst_buffer` **MUST** have been created with `vk::BufferUsageFlags::TRANSFER_DST`.
+    /// * The region **MUST** be within `dst_buffer`.
+    pub unsafe fn queue_buffer_upload(
+        &mut self,
+        dst_buffer: vk::Buffer,
+        dst_offset: u64,
+        data: &[u8],
+    ) -> bool {
+        let Some(src_offset) = (unsafe { self.write(data, STAGING_ALIGNMENT) }) else {
+            return false;
+        };
+
+        let region = vk::BufferCopy::default()
+            .src_offset(src_offset)
+            .dst_offset(dst_offset)
+            .size(data.len() as u64);
+
+        self.pending_copies
+            .push(PendingCopy::Buffer { dst_buffer, region });
+
+        true
+    }
+
+    /// Queues uploading tightly packed `pixels` to mip level `0` and array layer `0` of
+    /// `dst_image`. Returns `false` if the staging buffer does not have enough space, the pending
+    /// uploads should be submitted then this retried.
+    ///
+    /// # Panics
+    /// * If `texel_size` is zero.
+    ///
+    /// # Safety
+    /// * `dst_image` **MUST** have been created with `vk::ImageUsageFlags::TRANSFER_DST`.
+    /// * `dst_image` **MUST** be in `vk::ImageLayout::TRANSFER_DST_OPTIMAL` when the uploads are
+    ///   submitted.
+    /// * `pixels` **MUST** be tightly packed data for the image's format and `extent`.
+    /// * `texel_size` **MUST** be the size in bytes of a texel block of the image's format.
+    pub unsafe fn queue_image_upload(
+        &mut self,
+        dst_image: vk::Image,
+        extent: vk::Extent3D,
+        pixels: &[u8],
+        texel_size: u64,
+    ) -> bool {
+        assert!(texel_size > 0, "texel_size must be greater than zero");
+
+        let Some(src_offset) = (unsafe { self.write(pixels, texel_copy_alignment(texel_size)) })
+        else {
+            return false;
+        };
+
+        let region = vk::BufferImageCopy::default()
+            .buffer_offset(src_offset)
+            .buffer_row_length(0)
+            .buffer_image_height(0)
+            .image_subresource(
+                vk::ImageSubresourceLayers::default()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .mip_level(0)
+                    .base_array_layer(0)
+                    .layer_count(1),
+            )
+            .image_offset(vk::Offset3D::default())
+            .image_extent(extent);
+
+        self.pending_copies
+            .push(PendingCopy::Image { dst_image, region });
+
+        true
+    }
+
+    /// Records the copies for the queued uploads. The uploader **MUST** be reset with
+    /// [`Self::reset`] once the submission has completed.
+    ///
+    /// # Safety
+    /// * The staging buffer **MUST** have been flushed with [`Self::flush`] before the submission.
+    pub unsafe fn cmd_copy<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+    ) {
+        for copy in &self.pending_copies {
+            match copy {
+                PendingCopy::Buffer { dst_buffer, region } => unsafe {
+                    vulkan.device().cmd_copy_buffer(
+                        command_buffer,
+                        self.staging.buffer,
+                        *dst_buffer,
+                        slice::from_ref(region),
+                    )
+                },
+
+                PendingCopy::Image { dst_image, region } => unsafe {
+                    vulkan.device().cmd_copy_buffer_to_image(
+                        command_buffer,
+                        self.staging.buffer,
+                        *dst_image,
+                        vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                        slice::from_ref(region),
+                    )
+                },
+            }
+        }
+    }
+
+    /// Submits the queued uploads in a single submission and waits for completion, then resets the
+    /// uploader. Does nothing if there are no queued uploads.
+    pub unsafe fn submit<'m, Vulkan, Queue, Pool>(
+        &mut self,
+        vulkan: &Vulkan,
+        command_pool: Pool,
+        queue: Queue,
+        label: &str,
+    ) -> LabelledVkResult<()>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+        Pool: Into<MaybeMutex<'m, vk::CommandPool>>,
+    {
+        if self.pending_copies.is_empty() {
+            return Ok(());
+        }
+
+        unsafe { self.flush(vulkan) }?;
+
+        let cmd_fn = |vulkan: &Vulkan, command_buffer| unsafe {
+            self.cmd_copy(vulkan, command_buffer);
+        };
+
+        unsafe { onetime_command(vulkan, command_pool, queue, cmd_fn, label) }?;
+
+        self.reset();
+
+        Ok(())
+    }
+
+    /// Makes the queued uploads in the staging buffer available to the device, does nothing if the
+    /// memory is host-coherent.
+    pub unsafe fn flush<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) -> LabelledVkResult<()> {
+        if self.head == 0 {
+            return Ok(());
+        }
+
+        unsafe { self.staging.flush_range(vulkan, 0, self.head) }
+    }
+
+    /// Discards the queued uploads and frees the staging buffer for reuse.
+    ///
+    /// Any submissions copying from the staging buffer **MUST** have completed.
+    pub fn reset(&mut self) {
+        self.pending_copies.clear();
+        self.head = 0;
+    }
+
+    /// Destroys the staging buffer.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
+        unsafe { self.staging.destroy(vulkan) };
+    }
+
+    /// Writes `data` into the staging buffer at the next offset aligned to `alignment`, returning
+    /// the offset. Returns `None` if there is not enough space.
+    unsafe fn write(&mut self, data: &[u8], alignment: u64) -> Option<u64> {
+        let offset = align_up(self.head, alignment);
+        let end = offset.checked_add(data.len() as u64)?;
+        if end > self.staging.size {
+            return None;
+        }
+
+        let staging = unsafe { self.staging.as_mut_slice::<u8>() };
+        staging[offset as usize..end as usize].copy_from_slice(data);
+
+        self.head = end;
+
+        Some(offset)
+    }
+}
+
+/// Signals once the submission that copies from a [`StagingRing`] segment has completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StagingGuard {
+    /// A fence signalled by the submission.
+    Fence(vk::Fence),
+    /// A timeline semaphore signalled with a value by the submission.
+    Timeline(vk::Semaphore, u64),
+}
+
+/// A region of a [`StagingRing`] segment handed out by [`StagingRing::allocate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StagingAllocation {
+    /// The segment's buffer.
+    pub buffer: vk::Buffer,
+    /// The offset of the region in the buffer.
+    pub offset: u64,
+    /// The size of the region in bytes.
+    pub size: u64,
+}
+
+/// A ring of persistently mapped staging buffers for streaming uploads that overlap rendering.
+///
+/// Each frame the next segment is made current with [`StagingRing::begin`], which waits for the
+/// guard of the segment's previous submission. Regions are then allocated and copied from, before
+/// [`StagingRing::end`] flushes the segment and records the guard of the submission containing the
+/// copies.
+pub struct StagingRing {
+    /// The staging buffer of each segment.
+    segments: Vec<MappedBuffer>,
+    /// The guard of each segment's latest submission.
+    guards: Vec<Option<StagingGuard>>,
+    /// The index of the current segment.
+    current: usize,
+    /// The offset in the current segment of the next allocation.
+    head: u64,
+    /// The device's buffer alignment requirements.
+    alignment: BufferAlignment,
+}
+
+impl StagingRing {
+    /// Creates a ring of `segment_count` staging buffers of `segment_size` bytes each.
+    ///
+    /// # Panics
+    /// * If `segment_count` is zero.
+    pub unsafe fn new<Vulkan: VulkanContext>(
+        vulkan: &Vulkan,
+        segment_count: usize,
+        segment_size: u64,
+        label: &str,
+    ) -> Result<Self, AllocationError> {
+        assert!(
+            segment_count > 0,
+            "A StagingRing must have at least one segment"
+        );
+
+        let create_info = vk::BufferCreateInfo::default()
+            .size(segment_size)
+            .usage(vk::BufferUsageFlags::TRANSFER_SRC)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+
+        let mut segments = Vec::with_capacity(segment_count);
+        for index in 0..segment_count {
+            let segment = unsafe {
+                allocate_mapped_buffer(
+                    vulkan,
+                    &create_info,
+                    true,
+                    &format!("{label} Staging Ring {index}"),
+                )
+            };
+
+            match segment {
+                Ok(segment) => segments.push(segment),
+                Err(e) => {
+                    segments
+                        .iter()
+                        .for_each(|segment| unsafe { segment.destroy(vulkan) });
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(Self {
+            segments,
+            guards: vec![None; segment_count],
+            current: 0,
+            head: 0,
+            alignment: BufferAlignment::new(vulkan),
+        })
+    }
+
+    /// Makes the next segment current, waiting for its previous submission to complete.
+    pub unsafe fn begin<Vulkan: VulkanContext>(&mut self, vulkan: &Vulkan) -> LabelledVkResult<()> {
+        self.current = (self.current + 1) % self.segments.len();
+        self.head = 0;
+
+        if let Some(guard) = self.guards[self.current].take() {
+            unsafe { wait_for_guard(vulkan, guard) }?;
+        }
+
+        Ok(())
+    }
+
+    /// Allocates a region for `count` values of `T` in the current segment, aligned for `T`,
+    /// buffer copies, and `usage`. Returns `None` if the segment does not have enough space.
+    ///
+    /// The region's values are uninitialised and **MUST** all be written before the copy is
+    /// submitted.
+    pub fn allocate<T: Copy>(
+        &mut self,
+        count: usize,
+        usage: BufferUsageFlags,
+    ) -> Option<(StagingAllocation, &mut [MaybeUninit<T>])> {
+        let previous_end = align_up(self.head, STAGING_ALIGNMENT);
+        let (offset, end) = self.alignment.calc_slice(
+            previous_end,
+            align_of::<T>() as u64,
+            size_of::<T>() as u64,
+            count as u64,
+            usage,
+        );
+
+        let segment = &mut self.segments[self.current];
+        if end > segment.size {
+            return None;
+        }
+
+        self.head = end;
+
+        let allocation = StagingAllocation {
+            buffer: segment.buffer,
+            offset,
+            size: (count * size_of::<T>()) as u64,
+        };
+
+        // SAFETY: The region is within the mapped memory and `offset` is aligned for `T`.
+        let values = unsafe {
+            slice::from_raw_parts_mut(
+                segment
+                    .pointer
+                    .cast::<u8>()
+                    .add(offset as usize)
+                    .cast::<MaybeUninit<T>>(),
+                count,
+            )
+        };
+
+        Some((allocation, values))
+    }
+
+    /// Allocates a region in the current segment and writes `data` into it, see
+    /// [`Self::allocate`].
+    pub fn write<T: Copy>(&mut self, data: &[T]) -> Option<StagingAllocation> {
+        let (allocation, values) = self.allocate::<T>(data.len(), BufferUsageFlags::empty())?;
+        values.iter_mut().zip(data).for_each(|(value, &data)| {
+            value.write(data);
+        });
+
+        Some(allocation)
+    }
+
+    /// Allocates a region in the current segment aligned for copying to an image with texels of
+    /// `texel_size` bytes, see [`texel_copy_alignment`], and writes the tightly packed `pixels`
+    /// into it. Returns `None` if the segment does not have enough space.
+    ///
+    /// # Panics
+    /// * If `texel_size` is zero.
+    pub fn write_pixels(&mut self, pixels: &[u8], texel_size: u64) -> Option<StagingAllocation> {
+        assert!(texel_size > 0, "texel_size must be greater than zero");
+
+        let offset = align_up(self.head, texel_copy_alignment(texel_size));
+        let end = offset.checked_add(pixels.len() as u64)?;
+
+        let segment = &mut self.segments[self.current];
+        if end > segment.size {
+            return None;
+        }
+
+        let staging = unsafe { segment.as_mut_slice::<u8>() };
+        staging[offset as usize..end as usize].copy_from_slice(pixels);
+
+        self.head = end;
+
+        Some(StagingAllocation {
+            buffer: segment.buffer,
+            offset,
+            size: pixels.len() as u64,
+        })
+    }
+
+    /// Records copying `allocation` to `dst_buffer` at `dst_offset`.
+    ///
+    /// # Safety
+    /// * `dst_buffer` **MUST** have been created with `vk::BufferUsageFlags::TRANSFER_DST`.
+    /// * The region **MUST** be within `dst_buffer`.
+    pub unsafe fn cmd_copy_to_buffer<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+        allocation: StagingAllocation,
+        dst_buffer: vk::Buffer,
+        dst_offset: u64,
+    ) {
+        let region = vk::BufferCopy::default()
+            .src_offset(allocation.offset)
+            .dst_offset(dst_offset)
+            .size(allocation.size);
+
+        unsafe {
+            vulkan.device().cmd_copy_buffer(
+                command_buffer,
+                allocation.buffer,
+                dst_buffer,
+                slice::from_ref(&region),
+            )
+        };
+    }
+
+    /// Records copying tightly packed pixels in `allocation` to mip level `0` and array layer `0`
+    /// of `dst_image`.
+    ///
+    /// # Safety
+    /// * `allocation` **MUST** have been written by [`Self::write_pixels`] with the texel size of
+    ///   the image's format.
+    /// * `dst_image` **MUST** have been created with `vk::ImageUsageFlags::TRANSFER_DST`.
+    /// * `dst_image` **MUST** be in `vk::ImageLayout::TRANSFER_DST_OPTIMAL`.
+    pub unsafe fn cmd_copy_to_image<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+        allocation: StagingAllocation,
+        dst_image: vk::Image,
+        extent: vk::Extent3D,
+    ) {
+        let region = vk::BufferImageCopy::default()
+            .buffer_offset(allocation.offset)
+            .image_subresource(
+                vk::ImageSubresourceLayers::default()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .mip_level(0)
+                    .base_array_layer(0)
+                    .layer_count(1),
+            )
+            .image_extent(extent);
+
+        unsafe {
+            vulkan.device().cmd_copy_buffer_to_image(
+                command_buffer,
+                allocation.buffer,
+                dst_image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                slice::from_ref(&region),
+            )
+        };
+    }
+
+    /// Flushes the current segment and records `guard` as the signal that its copies have
+    /// completed.
+    ///
+    /// # Safety
+    /// * `guard` **MUST** be signalled by the submission containing the copies from the current
+    ///   segment.
+    /// * A guarding fence **MUST NOT** be reset or destroyed until the segment is next begun.
+    pub unsafe fn end<Vulkan: VulkanContext>(
+        &mut self,
+        vulkan: &Vulkan,
+        guard: StagingGuard,
+    ) -> LabelledVkResult<()> {
+        unsafe { self.segments[self.current].flush(vulkan) }?;
+        self.guards[self.current] = Some(guard);
+
+        Ok(())
+    }
+
+    /// Waits for every segment's submission to complete, then destroys the staging buffers.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(
+        &mut self,
+        vulkan: &Vulkan,
+    ) -> LabelledVkResult<()> {
+        for guard in self.guards.iter_mut().filter_map(Option::take) {
+            unsafe { wait_for_guard(vulkan, guard) }?;
+        }
+
+        self.segments
+            .iter()
+            .for_each(|segment| unsafe { segment.destroy(vulkan) });
+
+        Ok(())
+    }
+}
+
+/// Waits for `guard` to be signalled.
+unsafe fn wait_for_guard<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    guard: StagingGuard,
+) -> LabelledVkResult<()> {
+    match guard {
+        StagingGuard::Fence(fence) => unsafe {
+            vulkan
+                .device()
+                .wait_for_fences(slice::from_ref(&fence), true, u64::MAX)
+        }
+        .map_err(|e| VkError::new(e, "vkWaitForFences")),
+
+        StagingGuard::Timeline(semaphore, value) => {
+            let wait_info = vk::SemaphoreWaitInfo::default()
+                .semaphores(slice::from_ref(&semaphore))
+                .values(slice::from_ref(&value));
+
+            unsafe { vulkan.device().wait_semaphores(&wait_info, u64::MAX) }
+                .map_err(|e| VkError::new(e, "vkWaitSemaphores"))
+        }
+    }
+}
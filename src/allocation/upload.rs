@@ -0,0 +1,107 @@
+use core::slice;
+
+use ash::vk;
+
+use crate::{LabelledVkResult, MaybeMutex, VulkanContext, onetime_command};
+
+use super::{AllocationError, MappedBuffer, allocate_mapped_buffer};
+
+/// Uploads `data` to the start of `dst_buffer` through a single staging buffer of at most
+/// `chunk_size` bytes, copying one chunk at a time. Avoids allocating a staging buffer as large as
+/// the whole upload.
+///
+/// # Safety
+/// * `dst_buffer` **MUST** have been created with `vk::BufferUsageFlags::TRANSFER_DST`.
+/// * `dst_buffer` **MUST** be at least `data.len()` bytes.
+/// * `chunk_size` **MUST** be greater than zero.
+pub unsafe fn chunked_upload<'m, Vulkan, Queue, Pool>(
+    vulkan: &Vulkan,
+    command_pool: Pool,
+    queue: Queue,
+    dst_buffer: vk::Buffer,
+    data: &[u8],
+    chunk_size: u64,
+    label: &str,
+) -> Result<(), AllocationError>
+where
+    Vulkan: VulkanContext,
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    Pool: Into<MaybeMutex<'m, vk::CommandPool>>,
+{
+    if data.is_empty() {
+        return Ok(());
+    }
+
+    let staging_size = chunk_size.min(data.len() as u64);
+
+    // Create the staging buffer
+    let staging = {
+        let create_info = vk::BufferCreateInfo::default()
+            .size(staging_size)
+            .usage(vk::BufferUsageFlags::TRANSFER_SRC)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+
+        unsafe { allocate_mapped_buffer(vulkan, &create_info, true, &format!("{label} Staging")) }?
+    };
+
+    // Upload each chunk
+    let upload_result = unsafe {
+        upload_chunks(
+            vulkan,
+            command_pool.into(),
+            queue.into(),
+            &staging,
+            dst_buffer,
+            data,
+            label,
+        )
+    };
+
+    // Cleanup
+    unsafe { staging.destroy(vulkan) };
+
+    upload_result?;
+
+    Ok(())
+}
+
+/// Copies each chunk of `data` into the mapped staging buffer then into `dst_buffer`.
+unsafe fn upload_chunks<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    command_pool: MaybeMutex<'_, vk::CommandPool>,
+    queue: MaybeMutex<'_, vk::Queue>,
+    staging: &MappedBuffer,
+    dst_buffer: vk::Buffer,
+    data: &[u8],
+    label: &str,
+) -> LabelledVkResult<()> {
+    for (index, chunk) in data.chunks(staging.size as usize).enumerate() {
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                chunk.as_ptr(),
+                staging.pointer.cast::<u8>(),
+                chunk.len(),
+            )
+        };
+
+        unsafe { staging.flush_range(vulkan, 0, chunk.len() as u64) }?;
+
+        let region = vk::BufferCopy::default()
+            .src_offset(0)
+            .dst_offset(index as u64 * staging.size)
+            .size(chunk.len() as u64);
+
+        let cmd_fn = |vulkan: &Vulkan, command_buffer| unsafe {
+            vulkan.device().cmd_copy_buffer(
+                command_buffer,
+                staging.buffer,
+                dst_buffer,
+                slice::from_ref(&region),
+            )
+        };
+
+        unsafe { onetime_command(vulkan, command_pool, queue, cmd_fn, label) }?;
+    }
+
+    Ok(())
+}
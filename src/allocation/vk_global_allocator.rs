@@ -21,7 +21,8 @@ mod global_allocator {
     use alloc::collections::BTreeMap;
     use ash::vk;
     use core::alloc::Layout;
-    use parking_lot::Mutex;
+
+    use crate::maybe_mutex::{Mutex, lock_mutex};
 
     /// Static reference to the Vulkan callbacks to the global allocator.
     pub static VK_GLOBAL_ALLOCATOR: Option<std::sync::LazyLock<vk::AllocationCallbacks<'_>>> =
@@ -32,7 +33,7 @@ mod global_allocator {
 
     /// Returns the memory usage tracked by the Vulkan allocator callbacks.
     pub fn get_memory_usage() -> usize {
-        unsafe { LAYOUT_MAP.lock() }
+        unsafe { lock_mutex(&LAYOUT_MAP) }
             .values()
             .fold(0, |total, layout| total + layout.size())
     }
@@ -57,7 +58,7 @@ mod global_allocator {
         let layout = unsafe { Layout::from_size_align_unchecked(size, alignment) };
         let pointer = unsafe { alloc::alloc::alloc(layout) };
 
-        unsafe { LAYOUT_MAP.lock().insert(pointer, layout) };
+        unsafe { lock_mutex(&LAYOUT_MAP).insert(pointer, layout) };
 
         pointer as *mut core::ffi::c_void
     }
@@ -70,7 +71,7 @@ mod global_allocator {
             return;
         }
 
-        let maybe_layout = unsafe { LAYOUT_MAP.lock().remove(&(p_memory as *mut u8)) };
+        let maybe_layout = unsafe { lock_mutex(&LAYOUT_MAP).remove(&(p_memory as *mut u8)) };
 
         match maybe_layout {
             Some(layout) => unsafe { alloc::alloc::dealloc(p_memory as *mut u8, layout) },
@@ -85,7 +86,7 @@ mod global_allocator {
         alignment: usize,
         _allocation_scope: vk::SystemAllocationScope,
     ) -> *mut core::ffi::c_void {
-        let maybe_layout = unsafe { LAYOUT_MAP.lock().remove(&(p_original as *mut u8)) };
+        let maybe_layout = unsafe { lock_mutex(&LAYOUT_MAP).remove(&(p_original as *mut u8)) };
         let layout = match maybe_layout {
             Some(layout) => layout,
             None => panic!("Leaked memory with address: {p_original:?}"),
@@ -93,7 +94,7 @@ mod global_allocator {
 
         let pointer = unsafe { alloc::alloc::realloc(p_original as *mut u8, layout, size) };
         let new_layout = unsafe { Layout::from_size_align_unchecked(size, alignment) };
-        unsafe { LAYOUT_MAP.lock().insert(pointer, new_layout) };
+        unsafe { lock_mutex(&LAYOUT_MAP).insert(pointer, new_layout) };
 
         pointer as *mut core::ffi::c_void
     }
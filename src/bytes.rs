@@ -0,0 +1,67 @@
+use core::slice;
+
+/// Reinterprets a slice of `T` as its bytes.
+///
+/// # Safety
+/// * `T` **MUST NOT** contain padding bytes, as reading padding is undefined behaviour.
+pub unsafe fn as_bytes<T: Copy>(values: &[T]) -> &[u8] {
+    unsafe { slice::from_raw_parts(values.as_ptr().cast::<u8>(), size_of_val(values)) }
+}
+
+/// Reinterprets bytes as a slice of `T`, any trailing bytes that do not fit a whole `T` are
+/// excluded. Zero-sized types are rejected at compile time.
+///
+/// # Safety
+/// * `bytes` **MUST** be aligned to the alignment of `T`.
+/// * Every bit pattern in `bytes` **MUST** be a valid `T`.
+pub unsafe fn from_bytes<T: Copy>(bytes: &[u8]) -> &[T] {
+    const {
+        assert!(
+            size_of::<T>() > 0,
+            "from_bytes cannot reinterpret bytes as a zero-sized type"
+        );
+    }
+
+    debug_assert!(
+        bytes.as_ptr().cast::<T>().is_aligned(),
+        "bytes are not aligned for {}",
+        core::any::type_name::<T>()
+    );
+
+    let count = bytes.len() / size_of::<T>();
+
+    unsafe { slice::from_raw_parts(bytes.as_ptr().cast::<T>(), count) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{as_bytes, from_bytes};
+
+    #[test]
+    fn round_trips_values() {
+        let values = [1u32, 2, 3, 4];
+        let bytes = unsafe { as_bytes(&values) };
+
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(unsafe { from_bytes::<u32>(bytes) }, values);
+    }
+
+    #[test]
+    fn excludes_trailing_bytes() {
+        let values = [1u32, 2, 3, 4];
+        let bytes = unsafe { as_bytes(&values) };
+
+        assert_eq!(unsafe { from_bytes::<u32>(&bytes[..15]) }, [1, 2, 3]);
+        assert_eq!(unsafe { from_bytes::<u32>(&bytes[..3]) }, [] as [u32; 0]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not aligned")]
+    fn rejects_misaligned_bytes() {
+        let values = [1u32, 2, 3, 4];
+        let bytes = unsafe { as_bytes(&values) };
+
+        let _ = unsafe { from_bytes::<u32>(&bytes[1..]) };
+    }
+}
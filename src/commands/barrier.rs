@@ -0,0 +1,33 @@
+use core::slice;
+
+use ash::vk;
+
+use crate::VulkanContext;
+
+/// Records a global barrier where all prior commands and memory accesses complete and are visible
+/// before any subsequent commands start.
+///
+/// This is intentionally heavy-handed and stalls the GPU, it is a tool for debugging and bisecting
+/// synchronization bugs, not for production use.
+///
+/// # Safety
+/// * `synchronization2` **MUST** be enabled.
+pub unsafe fn cmd_full_barrier<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+) {
+    let memory_barrier = vk::MemoryBarrier2::default()
+        .src_stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+        .src_access_mask(vk::AccessFlags2::MEMORY_READ | vk::AccessFlags2::MEMORY_WRITE)
+        .dst_stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+        .dst_access_mask(vk::AccessFlags2::MEMORY_READ | vk::AccessFlags2::MEMORY_WRITE);
+
+    let dependency_info =
+        vk::DependencyInfo::default().memory_barriers(slice::from_ref(&memory_barrier));
+
+    unsafe {
+        vulkan
+            .device()
+            .cmd_pipeline_barrier2(command_buffer, &dependency_info)
+    };
+}
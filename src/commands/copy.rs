@@ -0,0 +1,113 @@
+use core::slice;
+
+use ash::vk;
+
+use crate::{VulkanContext, format_aspect_mask};
+
+/// Returns the subresource layers of `mip_level` and `layer_count` array layers from
+/// `base_array_layer` for copying an image of `format`.
+///
+/// A buffer-image copy can only copy a single aspect, so depth/stencil formats select `DEPTH`, the
+/// `STENCIL` aspect of combined formats **MUST** be copied separately.
+pub fn copy_subresource_layers(
+    format: vk::Format,
+    mip_level: u32,
+    base_array_layer: u32,
+    layer_count: u32,
+) -> vk::ImageSubresourceLayers {
+    let aspect_mask = format_aspect_mask(format);
+    let aspect_mask = if aspect_mask.contains(vk::ImageAspectFlags::DEPTH) {
+        vk::ImageAspectFlags::DEPTH
+    } else {
+        aspect_mask
+    };
+
+    vk::ImageSubresourceLayers::default()
+        .aspect_mask(aspect_mask)
+        .mip_level(mip_level)
+        .base_array_layer(base_array_layer)
+        .layer_count(layer_count)
+}
+
+/// Records copying tightly packed data from `buffer` at `buffer_offset` into the whole of
+/// `subresource` of `image`, whose mip level `0` has `image_extent`. A whole subresource always
+/// respects the queue family's [`transfer_granularity`](crate::transfer_granularity).
+///
+/// # Safety
+/// * `buffer` **MUST** have `TRANSFER_SRC` usage and hold the data for the whole subresource.
+/// * `image` **MUST** have `TRANSFER_DST` usage and be in `TRANSFER_DST_OPTIMAL`.
+/// * `subresource.aspect_mask` **MUST** be a single aspect of the image's format, see
+///   [`copy_subresource_layers`].
+pub unsafe fn cmd_copy_buffer_to_image<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+    buffer: vk::Buffer,
+    buffer_offset: vk::DeviceSize,
+    image: vk::Image,
+    image_extent: vk::Extent3D,
+    subresource: vk::ImageSubresourceLayers,
+) {
+    let region = buffer_image_region(buffer_offset, image_extent, subresource);
+
+    unsafe {
+        vulkan.device().cmd_copy_buffer_to_image(
+            command_buffer,
+            buffer,
+            image,
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            slice::from_ref(&region),
+        )
+    };
+}
+
+/// Records copying the whole of `subresource` of `image`, whose mip level `0` has
+/// `image_extent`, into `buffer` at `buffer_offset` as tightly packed data.
+///
+/// # Safety
+/// * `image` **MUST** have `TRANSFER_SRC` usage and be in `TRANSFER_SRC_OPTIMAL`.
+/// * `buffer` **MUST** have `TRANSFER_DST` usage and be large enough to hold the subresource.
+/// * `subresource.aspect_mask` **MUST** be a single aspect of the image's format, see
+///   [`copy_subresource_layers`].
+pub unsafe fn cmd_copy_image_to_buffer<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+    image: vk::Image,
+    image_extent: vk::Extent3D,
+    subresource: vk::ImageSubresourceLayers,
+    buffer: vk::Buffer,
+    buffer_offset: vk::DeviceSize,
+) {
+    let region = buffer_image_region(buffer_offset, image_extent, subresource);
+
+    unsafe {
+        vulkan.device().cmd_copy_image_to_buffer(
+            command_buffer,
+            image,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            buffer,
+            slice::from_ref(&region),
+        )
+    };
+}
+
+/// The region covering the whole of `subresource` with tightly packed buffer data.
+fn buffer_image_region(
+    buffer_offset: vk::DeviceSize,
+    image_extent: vk::Extent3D,
+    subresource: vk::ImageSubresourceLayers,
+) -> vk::BufferImageCopy {
+    let level = subresource.mip_level;
+    let extent = vk::Extent3D {
+        width: (image_extent.width >> level).max(1),
+        height: (image_extent.height >> level).max(1),
+        depth: (image_extent.depth >> level).max(1),
+    };
+
+    vk::BufferImageCopy::default()
+        .buffer_offset(buffer_offset)
+        .buffer_row_length(0)
+        .buffer_image_height(0)
+        .image_subresource(subresource)
+        .image_offset(vk::Offset3D::default())
+        .image_extent(extent)
+}
@@ -0,0 +1,101 @@
+use core::slice;
+
+use ash::{ext, vk};
+
+use crate::Context;
+
+/// Records setting the dynamic state required to draw with shader objects to sensible defaults,
+/// for a single colour attachment covering `extent`. Individual states can then be overridden.
+///
+/// Field                     | Value
+/// --------------------------|------
+/// viewport and scissor      | `extent`, depth `0.0..1.0`
+/// vertex input              | No bindings or attributes
+/// primitive topology        | `TRIANGLE_LIST`, no primitive restart
+/// rasterization             | `FILL`, cull `BACK`, `COUNTER_CLOCKWISE` front face, no discard
+/// multisampling             | `TYPE_1`, all samples, no alpha to coverage
+/// depth                     | Test and write enabled, `LESS`, no bias, clamp, or bounds test
+/// stencil                   | Disabled
+/// colour attachment 0       | Blending disabled, writes `RGBA`
+///
+/// The states are set through `VK_EXT_shader_object`'s entry points, so Vulkan 1.3 is not
+/// required.
+///
+/// # Safety
+/// * `VK_EXT_shader_object` **MUST** be enabled, see
+///   [`ShaderObject`](crate::requirements::ShaderObject).
+pub unsafe fn cmd_set_default_dynamic_state<Vulkan>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+    extent: vk::Extent2D,
+) where
+    Vulkan: Context<ext::shader_object::Device>,
+{
+    let shader_object: &ext::shader_object::Device = unsafe { vulkan.context() };
+
+    // Viewport and scissor
+    {
+        let viewport = vk::Viewport::default()
+            .x(0.0)
+            .y(0.0)
+            .width(extent.width as f32)
+            .height(extent.height as f32)
+            .min_depth(0.0)
+            .max_depth(1.0);
+
+        let scissor = vk::Rect2D::default().extent(extent);
+
+        unsafe {
+            shader_object.cmd_set_viewport_with_count(command_buffer, slice::from_ref(&viewport));
+            shader_object.cmd_set_scissor_with_count(command_buffer, slice::from_ref(&scissor));
+        }
+    }
+
+    // Vertex input and primitive assembly
+    unsafe {
+        shader_object.cmd_set_vertex_input(command_buffer, &[], &[]);
+        shader_object
+            .cmd_set_primitive_topology(command_buffer, vk::PrimitiveTopology::TRIANGLE_LIST);
+        shader_object.cmd_set_primitive_restart_enable(command_buffer, false);
+    }
+
+    // Rasterization
+    unsafe {
+        shader_object.cmd_set_rasterizer_discard_enable(command_buffer, false);
+        shader_object.cmd_set_polygon_mode(command_buffer, vk::PolygonMode::FILL);
+        shader_object.cmd_set_cull_mode(command_buffer, vk::CullModeFlags::BACK);
+        shader_object.cmd_set_front_face(command_buffer, vk::FrontFace::COUNTER_CLOCKWISE);
+        shader_object.cmd_set_depth_bias_enable(command_buffer, false);
+        shader_object.cmd_set_depth_clamp_enable(command_buffer, false);
+    }
+
+    // Multisampling
+    unsafe {
+        shader_object.cmd_set_rasterization_samples(command_buffer, vk::SampleCountFlags::TYPE_1);
+        shader_object.cmd_set_sample_mask(
+            command_buffer,
+            vk::SampleCountFlags::TYPE_1,
+            slice::from_ref(&u32::MAX),
+        );
+        shader_object.cmd_set_alpha_to_coverage_enable(command_buffer, false);
+    }
+
+    // Depth and stencil
+    unsafe {
+        shader_object.cmd_set_depth_test_enable(command_buffer, true);
+        shader_object.cmd_set_depth_write_enable(command_buffer, true);
+        shader_object.cmd_set_depth_compare_op(command_buffer, vk::CompareOp::LESS);
+        shader_object.cmd_set_depth_bounds_test_enable(command_buffer, false);
+        shader_object.cmd_set_stencil_test_enable(command_buffer, false);
+    }
+
+    // Colour blending
+    unsafe {
+        shader_object.cmd_set_color_blend_enable(command_buffer, 0, slice::from_ref(&vk::FALSE));
+        shader_object.cmd_set_color_write_mask(
+            command_buffer,
+            0,
+            slice::from_ref(&vk::ColorComponentFlags::RGBA),
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use core::slice;
+
+use ash::vk;
+
+use crate::{VulkanContext, align_up, format_properties, texel_copy_alignment};
+
+use super::transition_image::pipeline_stage_access_tuple;
+
+/// Returns the number of mip levels in a full chain for `extent`, down to `1x1`.
+pub fn mip_level_count(extent: vk::Extent2D) -> u32 {
+    u32::BITS - extent.width.max(extent.height).max(1).leading_zeros()
+}
+
+/// Returns the extent of mip `level` for a base `extent`, each dimension is at least `1`.
+pub fn mip_level_extent(extent: vk::Extent2D, level: u32) -> vk::Extent2D {
+    vk::Extent2D {
+        width: (extent.width >> level).max(1),
+        height: (extent.height >> level).max(1),
+    }
+}
+
+/// Returns the filter [`cmd_generate_mipmaps`] should use for `format` with optimal tiling,
+/// `LINEAR` if supported else `NEAREST`. Returns `None` if `format` does not support blitting,
+/// in which case the levels should be generated on the host and copied with [`cmd_copy_mipmaps`].
+pub fn mipmap_blit_filter<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    format: vk::Format,
+) -> Option<vk::Filter> {
+    let features = format_properties(vulkan, format).optimal_tiling_features;
+
+    if !features.contains(vk::FormatFeatureFlags::BLIT_SRC | vk::FormatFeatureFlags::BLIT_DST) {
+        return None;
+    }
+
+    if features.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR) {
+        Some(vk::Filter::LINEAR)
+    } else {
+        Some(vk::Filter::NEAREST)
+    }
+}
+
+/// Records generating mip levels `1..mip_levels` of a colour image by blitting each level from the
+/// previous level, then transitions every level to `final_layout`.
+///
+/// Supports the same final layouts as [`crate::cmd_transition_image`], returns `None` without
+/// recording if `final_layout` is unsupported.
+///
+/// # Safety
+/// * Every level of `image` **MUST** be in `TRANSFER_DST_OPTIMAL` with level `0` written.
+/// * `image` **MUST** have `TRANSFER_SRC` and `TRANSFER_DST` usage.
+/// * `filter` **MUST** be supported for the image's format, see [`mipmap_blit_filter`].
+pub unsafe fn cmd_generate_mipmaps<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+    image: vk::Image,
+    extent: vk::Extent2D,
+    mip_levels: u32,
+    filter: vk::Filter,
+    final_layout: vk::ImageLayout,
+) -> Option<()> {
+    let (final_stage, final_access) = pipeline_stage_access_tuple(final_layout)?;
+
+    for level in 1..mip_levels {
+        let src_level = level - 1;
+
+        // Wait for the previous level to be written
+        unsafe {
+            cmd_level_barrier(
+                vulkan,
+                command_buffer,
+                image,
+                src_level,
+                (
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::AccessFlags::TRANSFER_WRITE,
+                ),
+                (
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::AccessFlags::TRANSFER_READ,
+                ),
+            )
+        };
+
+        // Blit the previous level into this level
+        {
+            let src_extent = mip_level_extent(extent, src_level);
+            let dst_extent = mip_level_extent(extent, level);
+
+            let blit = vk::ImageBlit::default()
+                .src_subresource(colour_layers(src_level))
+                .src_offsets([
+                    vk::Offset3D::default(),
+                    vk::Offset3D {
+                        x: src_extent.width as i32,
+                        y: src_extent.height as i32,
+                        z: 1,
+                    },
+                ])
+                .dst_subresource(colour_layers(level))
+                .dst_offsets([
+                    vk::Offset3D::default(),
+                    vk::Offset3D {
+                        x: dst_extent.width as i32,
+                        y: dst_extent.height as i32,
+                        z: 1,
+                    },
+                ]);
+
+            unsafe {
+                vulkan.device().cmd_blit_image(
+                    command_buffer,
+                    image,
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    image,
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    slice::from_ref(&blit),
+                    filter,
+                )
+            };
+        }
+
+        // The previous level is complete
+        unsafe {
+            cmd_level_barrier(
+                vulkan,
+                command_buffer,
+                image,
+                src_level,
+                (
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::AccessFlags::TRANSFER_READ,
+                ),
+                (final_layout, final_stage, final_access),
+            )
+        };
+    }
+
+    // The last level is never blitted from
+    unsafe {
+        cmd_level_barrier(
+            vulkan,
+            command_buffer,
+            image,
+            mip_levels.max(1) - 1,
+            (
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::AccessFlags::TRANSFER_WRITE,
+            ),
+            (final_layout, final_stage, final_access),
+        )
+    };
+
+    Some(())
+}
+
+/// Returns the offset and extent of each mip level when the levels of an image with texels of
+/// `texel_size` bytes are tightly packed into a buffer, with each offset aligned for
+/// `vkCmdCopyBufferToImage`. Used to lay out host generated levels for [`cmd_copy_mipmaps`].
+///
+/// # Panics
+/// * If `texel_size` is zero.
+pub fn mip_chain_layout(
+    extent: vk::Extent2D,
+    mip_levels: u32,
+    texel_size: u64,
+) -> Vec<(vk::DeviceSize, vk::Extent2D)> {
+    assert!(texel_size > 0, "texel_size must be greater than zero");
+
+    let alignment = texel_copy_alignment(texel_size);
+
+    let mut offset = 0;
+    (0..mip_levels)
+        .map(|level| {
+            let level_extent = mip_level_extent(extent, level);
+            let level_offset = offset;
+
+            offset += u64::from(level_extent.width) * u64::from(level_extent.height) * texel_size;
+            offset = align_up(offset, alignment);
+
+            (level_offset, level_extent)
+        })
+        .collect()
+}
+
+/// Records copying host generated mip levels from `buffer`, laid out by [`mip_chain_layout`],
+/// into every level of a colour image, then transitions every level to `final_layout`. A fallback
+/// for formats that do not support blitting.
+///
+/// Supports the same final layouts as [`crate::cmd_transition_image`], returns `None` without
+/// recording if `final_layout` is unsupported.
+///
+/// # Safety
+/// * Every level of `image` **MUST** be in `TRANSFER_DST_OPTIMAL`.
+/// * `buffer` **MUST** have `TRANSFER_SRC` usage and hold every level.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn cmd_copy_mipmaps<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+    buffer: vk::Buffer,
+    image: vk::Image,
+    extent: vk::Extent2D,
+    mip_levels: u32,
+    texel_size: u64,
+    final_layout: vk::ImageLayout,
+) -> Option<()> {
+    let (final_stage, final_access) = pipeline_stage_access_tuple(final_layout)?;
+
+    let regions: Vec<_> = mip_chain_layout(extent, mip_levels, texel_size)
+        .into_iter()
+        .enumerate()
+        .map(|(level, (offset, level_extent))| {
+            vk::BufferImageCopy::default()
+                .buffer_offset(offset)
+                .image_subresource(colour_layers(level as u32))
+                .image_extent(vk::Extent3D {
+                    width: level_extent.width,
+                    height: level_extent.height,
+                    depth: 1,
+                })
+        })
+        .collect();
+
+    unsafe {
+        vulkan.device().cmd_copy_buffer_to_image(
+            command_buffer,
+            buffer,
+            image,
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            &regions,
+        )
+    };
+
+    let image_barrier = vk::ImageMemoryBarrier::default()
+        .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+        .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .new_layout(final_layout)
+        .dst_access_mask(final_access)
+        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .image(image)
+        .subresource_range(
+            vk::ImageSubresourceRange::default()
+                .aspect_mask(vk::ImageAspectFlags::COLOR)
+                .base_array_layer(0)
+                .base_mip_level(0)
+                .layer_count(1)
+                .level_count(mip_levels),
+        );
+
+    unsafe {
+        vulkan.device().cmd_pipeline_barrier(
+            command_buffer,
+            vk::PipelineStageFlags::TRANSFER,
+            final_stage,
+            vk::DependencyFlags::empty(),
+            &[],
+            &[],
+            slice::from_ref(&image_barrier),
+        )
+    };
+
+    Some(())
+}
+
+/// The colour subresource layers of mip `level`.
+fn colour_layers(level: u32) -> vk::ImageSubresourceLayers {
+    vk::ImageSubresourceLayers::default()
+        .aspect_mask(vk::ImageAspectFlags::COLOR)
+        .mip_level(level)
+        .base_array_layer(0)
+        .layer_count(1)
+}
+
+/// Records a barrier for a single mip level, each state is `(layout, stage, access)`.
+unsafe fn cmd_level_barrier<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+    image: vk::Image,
+    level: u32,
+    (old_layout, src_stage, src_access): (vk::ImageLayout, vk::PipelineStageFlags, vk::AccessFlags),
+    (new_layout, dst_stage, dst_access): (vk::ImageLayout, vk::PipelineStageFlags, vk::AccessFlags),
+) {
+    let image_barrier = vk::ImageMemoryBarrier::default()
+        .old_layout(old_layout)
+        .src_access_mask(src_access)
+        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .new_layout(new_layout)
+        .dst_access_mask(dst_access)
+        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .image(image)
+        .subresource_range(
+            vk::ImageSubresourceRange::default()
+                .aspect_mask(vk::ImageAspectFlags::COLOR)
+                .base_array_layer(0)
+                .base_mip_level(level)
+                .layer_count(1)
+                .level_count(1),
+        );
+
+    unsafe {
+        vulkan.device().cmd_pipeline_barrier(
+            command_buffer,
+            src_stage,
+            dst_stage,
+            vk::DependencyFlags::empty(),
+            &[],
+            &[],
+            slice::from_ref(&image_barrier),
+        )
+    };
+}
@@ -1,5 +1,33 @@
-pub use transient::onetime_command;
-pub use transition_image::cmd_transition_image;
+pub use barrier::cmd_full_barrier;
+pub use copy::{cmd_copy_buffer_to_image, cmd_copy_image_to_buffer, copy_subresource_layers};
+pub use dynamic_state::cmd_set_default_dynamic_state;
+pub use mipmap::{
+    cmd_copy_mipmaps, cmd_generate_mipmaps, mip_chain_layout, mip_level_count, mip_level_extent,
+    mipmap_blit_filter,
+};
+pub use overlay::OverlayRecorder;
+pub use pass::profiled_pass;
+pub use push_constants::cmd_push_constants;
+pub use record::record_command_buffer;
+pub use reduce::{PassInfo, PingPongReduction, ReductionPlan, ReductionPlanError, reduction_plan};
+pub use ring::{SubmissionRing, SubmissionSlot};
+pub use submit::submit_mixed;
+pub use transient::{
+    onetime_command, onetime_command_reuse, onetime_command_timeline, with_transient_pool,
+};
+pub(crate) use transition_image::is_supported_layout;
+pub use transition_image::{cmd_transition_and_transfer, cmd_transition_image};
 
+mod barrier;
+mod copy;
+mod dynamic_state;
+mod mipmap;
+mod overlay;
+mod pass;
+mod push_constants;
+mod record;
+mod reduce;
+mod ring;
+mod submit;
 mod transient;
 mod transition_image;
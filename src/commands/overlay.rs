@@ -0,0 +1,124 @@
+use ash::vk;
+
+use crate::{LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, try_name};
+
+/// Owns a secondary command buffer that is re-recorded each frame within a render pass, e.g., for
+/// an immediate mode UI drawn over the scene. The primary command buffer executes it with
+/// `vkCmdExecuteCommands`.
+pub struct OverlayRecorder {
+    /// The command pool the command buffer is allocated from.
+    pub command_pool: vk::CommandPool,
+    /// The secondary command buffer.
+    pub command_buffer: vk::CommandBuffer,
+}
+
+impl OverlayRecorder {
+    /// Creates the command pool and secondary command buffer on the context's queue family.
+    pub unsafe fn new<Vulkan: VulkanContext>(
+        vulkan: &Vulkan,
+        label: &str,
+    ) -> LabelledVkResult<Self> {
+        let command_pool = {
+            let create_info = vk::CommandPoolCreateInfo::default()
+                .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+                .queue_family_index(vulkan.queue_family_index());
+
+            let command_pool = unsafe {
+                vulkan
+                    .device()
+                    .create_command_pool(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+            }
+            .map_err(|e| VkError::new(e, "vkCreateCommandPool"))?;
+
+            unsafe { try_name(vulkan, command_pool, &format!("{label} Command Pool")) };
+
+            command_pool
+        };
+
+        let command_buffer = {
+            let allocate_info = vk::CommandBufferAllocateInfo::default()
+                .command_buffer_count(1)
+                .command_pool(command_pool)
+                .level(vk::CommandBufferLevel::SECONDARY);
+
+            let command_buffer =
+                match unsafe { vulkan.device().allocate_command_buffers(&allocate_info) } {
+                    Ok(command_buffers) => command_buffers[0],
+                    Err(e) => {
+                        unsafe {
+                            vulkan
+                                .device()
+                                .destroy_command_pool(command_pool, VK_GLOBAL_ALLOCATOR.as_deref())
+                        };
+
+                        return Err(VkError::new(e, "vkAllocateCommandBuffers"));
+                    }
+                };
+
+            unsafe { try_name(vulkan, command_buffer, &format!("{label} Command Buffer")) };
+
+            command_buffer
+        };
+
+        Ok(Self {
+            command_pool,
+            command_buffer,
+        })
+    }
+
+    /// Resets the command buffer and begins recording it to continue a render pass.
+    ///
+    /// # Safety
+    /// * The previous submission that executed the command buffer **MUST** have completed.
+    /// * `inheritance` **MUST** describe the render pass, or contain a
+    ///   `vk::CommandBufferInheritanceRenderingInfo` for dynamic rendering, the command buffer is
+    ///   executed in.
+    pub unsafe fn begin_frame<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        inheritance: &vk::CommandBufferInheritanceInfo<'_>,
+    ) -> LabelledVkResult<vk::CommandBuffer> {
+        unsafe {
+            vulkan
+                .device()
+                .reset_command_buffer(self.command_buffer, vk::CommandBufferResetFlags::empty())
+        }
+        .map_err(|e| VkError::new(e, "vkResetCommandBuffer"))?;
+
+        let begin_info = vk::CommandBufferBeginInfo::default()
+            .flags(
+                vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT
+                    | vk::CommandBufferUsageFlags::RENDER_PASS_CONTINUE,
+            )
+            .inheritance_info(inheritance);
+
+        unsafe {
+            vulkan
+                .device()
+                .begin_command_buffer(self.command_buffer, &begin_info)
+        }
+        .map_err(|e| VkError::new(e, "vkBeginCommandBuffer"))?;
+
+        Ok(self.command_buffer)
+    }
+
+    /// Ends recording, returning the command buffer to be executed by the primary command buffer.
+    pub unsafe fn finish<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+    ) -> LabelledVkResult<vk::CommandBuffer> {
+        unsafe { vulkan.device().end_command_buffer(self.command_buffer) }
+            .map_err(|e| VkError::new(e, "vkEndCommandBuffer"))?;
+
+        Ok(self.command_buffer)
+    }
+
+    /// Destroys the command pool and command buffer.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
+        unsafe {
+            vulkan
+                .device()
+                .destroy_command_pool(self.command_pool, VK_GLOBAL_ALLOCATOR.as_deref())
+        };
+    }
+}
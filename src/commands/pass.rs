@@ -0,0 +1,38 @@
+use ash::vk;
+
+use crate::{TimestampProfiler, VulkanContext, cmd_try_begin_label, cmd_try_end_label};
+
+/// Records `cmd_fn` within a debug label, bracketed by a timestamp at the top of the pipe and a
+/// timestamp at the bottom of the pipe. Returns the start and end marks, or `None` if the profiler
+/// did not have capacity for both timestamps. `cmd_fn` is always recorded.
+pub unsafe fn profiled_pass<Vulkan, CmdFn>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+    profiler: &mut TimestampProfiler,
+    label: &str,
+    cmd_fn: CmdFn,
+) -> Option<(u32, u32)>
+where
+    Vulkan: VulkanContext,
+    CmdFn: FnOnce(&Vulkan, vk::CommandBuffer),
+{
+    unsafe { cmd_try_begin_label(vulkan, command_buffer, label) };
+
+    let start = unsafe {
+        profiler.cmd_write_timestamp(vulkan, command_buffer, vk::PipelineStageFlags::TOP_OF_PIPE)
+    };
+
+    cmd_fn(vulkan, command_buffer);
+
+    let end = unsafe {
+        profiler.cmd_write_timestamp(
+            vulkan,
+            command_buffer,
+            vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+        )
+    };
+
+    unsafe { cmd_try_end_label(vulkan, command_buffer) };
+
+    start.zip(end)
+}
@@ -0,0 +1,59 @@
+use core::slice;
+
+use ash::vk;
+
+use crate::{VulkanContext, as_bytes};
+
+/// Records pushing `value` as push constants at `offset` for `stages`.
+///
+/// In debug builds, asserts that `offset` and the size of `T` are multiples of four and within
+/// `max_push_constants_size`, the device's `maxPushConstantsSize`, and if `push_constant_ranges` is
+/// not empty, that a range covers the push for all of `stages`.
+///
+/// # Safety
+/// * `push_constant_ranges` **MUST** be empty or the ranges `layout` was created with.
+/// * `T` **MUST** match the layout of the shaders' push constant blocks at `offset`.
+/// * `T` **MUST NOT** contain padding bytes, as reading padding is undefined behaviour.
+pub unsafe fn cmd_push_constants<Vulkan, T>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+    layout: vk::PipelineLayout,
+    push_constant_ranges: &[vk::PushConstantRange],
+    max_push_constants_size: u32,
+    stages: vk::ShaderStageFlags,
+    offset: u32,
+    value: &T,
+) where
+    Vulkan: VulkanContext,
+    T: Copy,
+{
+    let size = size_of::<T>() as u32;
+
+    debug_assert!(
+        offset % 4 == 0 && size % 4 == 0,
+        "Push constant offset {offset} and size {size} must be multiples of 4"
+    );
+
+    debug_assert!(
+        offset + size <= max_push_constants_size,
+        "Push constant offset {offset} and size {size} exceed maxPushConstantsSize {max_push_constants_size}"
+    );
+
+    debug_assert!(
+        push_constant_ranges.is_empty()
+            || push_constant_ranges.iter().any(|range| {
+                range.stage_flags.contains(stages)
+                    && range.offset <= offset
+                    && offset + size <= range.offset + range.size
+            }),
+        "No push constant range covers offset {offset} and size {size} for {stages:?}"
+    );
+
+    let bytes = unsafe { as_bytes(slice::from_ref(value)) };
+
+    unsafe {
+        vulkan
+            .device()
+            .cmd_push_constants(command_buffer, layout, stages, offset, bytes)
+    };
+}
@@ -0,0 +1,35 @@
+use ash::vk;
+
+use crate::{LabelledVkResult, VkError, VulkanContext};
+
+/// Begins `command_buffer` with `flags`, records `cmd_fn` into it, then ends it without
+/// submitting. Useful for submitting several command buffers together or wrapping `cmd_fn` in a
+/// rendering scope.
+///
+/// # Safety
+/// * `command_buffer` **MUST** be in the initial state.
+pub unsafe fn record_command_buffer<Vulkan, CmdFn>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+    flags: vk::CommandBufferUsageFlags,
+    cmd_fn: CmdFn,
+) -> LabelledVkResult<()>
+where
+    Vulkan: VulkanContext,
+    CmdFn: FnOnce(&Vulkan, vk::CommandBuffer),
+{
+    let begin_info = vk::CommandBufferBeginInfo::default().flags(flags);
+    unsafe {
+        vulkan
+            .device()
+            .begin_command_buffer(command_buffer, &begin_info)
+            .map_err(|e| VkError::new(e, "vkBeginCommandBuffer"))?;
+    }
+
+    cmd_fn(vulkan, command_buffer);
+
+    unsafe { vulkan.device().end_command_buffer(command_buffer) }
+        .map_err(|e| VkError::new(e, "vkEndCommandBuffer"))?;
+
+    Ok(())
+}
@@ -0,0 +1,265 @@
+use core::slice;
+
+use ash::vk;
+use thiserror::Error;
+
+use crate::{
+    LabelledVkResult, MaybeMutex, VulkanContext, cmd_push_constants, onetime_command_timeline,
+};
+
+/// The minimum `maxPushConstantsSize` required by the spec, which the `u32` input length always fits
+/// within.
+const MIN_MAX_PUSH_CONSTANTS_SIZE: u32 = 128;
+
+/// Records the passes of an iterative compute reduction that ping-pongs between two buffer
+/// regions until a single output remains.
+///
+/// Each pass binds `descriptor_sets[pass % 2]`, which **MUST** read from region `pass % 2` and
+/// write to the other region, pushes the pass's input length as a `u32` push constant at offset
+/// `0`, then dispatches.
+pub struct PingPongReduction<DispatchFn>
+where
+    DispatchFn: Fn(u32) -> (u32, u32),
+{
+    /// The compute pipeline that performs a single pass.
+    pub pipeline: vk::Pipeline,
+    /// The pipeline's layout.
+    pub layout: vk::PipelineLayout,
+    /// The descriptor sets for reading region `0` and writing region `1`, and for reading region
+    /// `1` and writing region `0`.
+    pub descriptor_sets: [vk::DescriptorSet; 2],
+    /// Returns the number of workgroups to dispatch and the output length of a pass for an input
+    /// length.
+    pub dispatch_fn: DispatchFn,
+}
+
+impl<DispatchFn> PingPongReduction<DispatchFn>
+where
+    DispatchFn: Fn(u32) -> (u32, u32),
+{
+    /// Records every pass to reduce `input_length` values, with a barrier between each pass.
+    /// Returns the index of the region that holds the output.
+    ///
+    /// # Panics
+    /// * If `dispatch_fn` returns an output length that is not less than the input length, as the
+    ///   reduction would never complete.
+    pub unsafe fn cmd_reduce<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+        input_length: u32,
+    ) -> usize {
+        unsafe {
+            vulkan.device().cmd_bind_pipeline(
+                command_buffer,
+                vk::PipelineBindPoint::COMPUTE,
+                self.pipeline,
+            )
+        };
+
+        let mut input_length = input_length;
+        let mut pass = 0;
+        while input_length > 1 {
+            let (dispatches, output_length) = (self.dispatch_fn)(input_length);
+            assert!(
+                output_length < input_length,
+                "Reduction pass {pass} does not reduce its input length of {input_length}, output length is {output_length}"
+            );
+
+            // Wait for the previous pass's writes
+            if pass > 0 {
+                let memory_barrier = vk::MemoryBarrier::default()
+                    .src_access_mask(vk::AccessFlags::SHADER_WRITE)
+                    .dst_access_mask(vk::AccessFlags::SHADER_READ);
+
+                unsafe {
+                    vulkan.device().cmd_pipeline_barrier(
+                        command_buffer,
+                        vk::PipelineStageFlags::COMPUTE_SHADER,
+                        vk::PipelineStageFlags::COMPUTE_SHADER,
+                        vk::DependencyFlags::empty(),
+                        slice::from_ref(&memory_barrier),
+                        &[],
+                        &[],
+                    )
+                };
+            }
+
+            // Record the pass
+            unsafe {
+                vulkan.device().cmd_bind_descriptor_sets(
+                    command_buffer,
+                    vk::PipelineBindPoint::COMPUTE,
+                    self.layout,
+                    0,
+                    slice::from_ref(&self.descriptor_sets[pass % 2]),
+                    &[],
+                );
+
+                cmd_push_constants(
+                    vulkan,
+                    command_buffer,
+                    self.layout,
+                    &[],
+                    MIN_MAX_PUSH_CONSTANTS_SIZE,
+                    vk::ShaderStageFlags::COMPUTE,
+                    0,
+                    &input_length,
+                );
+
+                vulkan
+                    .device()
+                    .cmd_dispatch(command_buffer, dispatches, 1, 1);
+            }
+
+            input_length = output_length;
+            pass += 1;
+        }
+
+        pass % 2
+    }
+
+    /// Records and submits every pass to reduce `input_length` values as a single submission that
+    /// signals `semaphore` with `signal_value`, then waits for completion. Returns the index of
+    /// the region that holds the output.
+    ///
+    /// # Panics
+    /// * If a pass does not reduce its input, see [`Self::cmd_reduce`].
+    ///
+    /// # Safety
+    /// * `semaphore` **MUST** be a timeline semaphore.
+    /// * `signal_value` **MUST** be greater than the current value of `semaphore` and any pending
+    ///   signal operations.
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn reduce<'m, Vulkan, Queue, Pool>(
+        &self,
+        vulkan: &Vulkan,
+        command_pool: Pool,
+        queue: Queue,
+        semaphore: vk::Semaphore,
+        signal_value: u64,
+        input_length: u32,
+        label: &str,
+    ) -> LabelledVkResult<usize>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+        Pool: Into<MaybeMutex<'m, vk::CommandPool>>,
+    {
+        let mut output_region = 0;
+
+        let cmd_fn = |vulkan: &Vulkan, command_buffer| unsafe {
+            output_region = self.cmd_reduce(vulkan, command_buffer, input_length);
+        };
+
+        unsafe {
+            onetime_command_timeline(
+                vulkan,
+                command_pool,
+                queue,
+                semaphore,
+                signal_value,
+                cmd_fn,
+                label,
+            )
+        }?;
+
+        Ok(output_region)
+    }
+}
+
+/// A single pass of a reduction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PassInfo {
+    /// The number of values the pass reads.
+    pub input: u32,
+    /// The number of workgroups the pass dispatches.
+    pub dispatches: u32,
+    /// The number of values the pass writes.
+    pub output: u32,
+}
+
+/// Every pass of a reduction, precomputed to size buffers and command rings before recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReductionPlan {
+    /// The passes in submission order.
+    pub passes: Vec<PassInfo>,
+}
+
+impl ReductionPlan {
+    /// Returns the index of the region that holds the output, matching
+    /// [`PingPongReduction::cmd_reduce`].
+    pub fn output_region(&self) -> usize {
+        self.passes.len() % 2
+    }
+}
+
+/// Reduction plan failure reason.
+#[derive(Debug, Error)]
+pub enum ReductionPlanError {
+    /// The workgroup size cannot be reduced to a single value with two levels of subgroup
+    /// operations.
+    #[error(
+        "Workgroup size {workgroup_size} must be a non-zero multiple of subgroup size {subgroup_size} and at most its square"
+    )]
+    InvalidWorkgroupSize {
+        /// The workgroup size.
+        workgroup_size: u32,
+        /// The subgroup size.
+        subgroup_size: u32,
+    },
+
+    /// A pass would not reduce its input, so the reduction would never complete.
+    #[error("Pass {pass} does not reduce its input length of {input_length}")]
+    PassDoesNotReduce {
+        /// The index of the pass.
+        pass: usize,
+        /// The input length of the pass.
+        input_length: u32,
+    },
+}
+
+/// Precomputes every pass to reduce `input_length` values, where each workgroup of
+/// `workgroup_size` invocations reduces up to `workgroup_size` values to one using two levels of
+/// subgroup operations.
+pub fn reduction_plan(
+    input_length: u32,
+    subgroup_size: u32,
+    workgroup_size: u32,
+) -> Result<ReductionPlan, ReductionPlanError> {
+    let is_valid_workgroup_size = subgroup_size > 0
+        && workgroup_size > 0
+        && workgroup_size % subgroup_size == 0
+        && workgroup_size / subgroup_size <= subgroup_size;
+    if !is_valid_workgroup_size {
+        return Err(ReductionPlanError::InvalidWorkgroupSize {
+            workgroup_size,
+            subgroup_size,
+        });
+    }
+
+    let mut passes = vec![];
+
+    let mut input = input_length;
+    while input > 1 {
+        let dispatches = input.div_ceil(workgroup_size);
+        let output = dispatches;
+
+        if output >= input {
+            return Err(ReductionPlanError::PassDoesNotReduce {
+                pass: passes.len(),
+                input_length: input,
+            });
+        }
+
+        passes.push(PassInfo {
+            input,
+            dispatches,
+            output,
+        });
+
+        input = output;
+    }
+
+    Ok(ReductionPlan { passes })
+}
@@ -0,0 +1,234 @@
+use core::{slice, time::Duration};
+
+use ash::vk;
+
+use crate::{
+    LabelledVkResult, MaybeMutex, TimelineSemaphore, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext,
+    logging::error, try_name,
+};
+
+/// A command pool and its command buffer in a [`SubmissionRing`].
+#[derive(Debug, Clone, Copy)]
+pub struct SubmissionSlot {
+    /// This slot's command pool.
+    pub command_pool: vk::CommandPool,
+    /// That command pool's command buffer.
+    pub command_buffer: vk::CommandBuffer,
+    /// The index of this slot in the ring.
+    pub index: usize,
+}
+
+/// A ring of command pools and buffers guarded by a timeline semaphore, so work can be recorded
+/// into one slot while the submissions of the other slots are in flight.
+///
+/// Every submission signals the next timeline value, a slot is only reused once the value of its
+/// previous submission has been signalled.
+pub struct SubmissionRing {
+    /// The timeline semaphore that is signalled with a submission's value once it completes.
+    pub semaphore: TimelineSemaphore,
+    /// The slots in the ring.
+    pub slots: Vec<SubmissionSlot>,
+    /// The timeline value each slot's latest submission signals, `0` if never submitted.
+    pub slot_values: Vec<u64>,
+    /// The index of the next slot to acquire.
+    pub next_slot: usize,
+}
+
+impl SubmissionRing {
+    /// Creates a ring of `count` slots for queues from `family_index`.
+    ///
+    /// # Safety
+    /// * The device **MUST** have the `timelineSemaphore` feature enabled, see
+    ///   [`crate::requirements::TimelineSemaphore`].
+    ///
+    /// # Panics
+    /// * If `count` is zero.
+    pub unsafe fn new<Vulkan: VulkanContext>(
+        vulkan: &Vulkan,
+        family_index: u32,
+        count: usize,
+        label: &str,
+    ) -> LabelledVkResult<Self> {
+        assert!(count > 0, "A SubmissionRing must have at least one slot");
+
+        let semaphore = unsafe { TimelineSemaphore::new(vulkan, 0, &format!("{label} Ring")) }?;
+
+        let mut ring = Self {
+            semaphore,
+            slots: Vec::with_capacity(count),
+            slot_values: vec![0; count],
+            next_slot: 0,
+        };
+
+        for index in 0..count {
+            match unsafe { Self::create_slot(vulkan, family_index, index, label) } {
+                Ok(slot) => ring.slots.push(slot),
+                Err(e) => {
+                    unsafe { ring.destroy(vulkan) };
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(ring)
+    }
+
+    /// Waits for the next slot's previous submission to complete, then resets its command pool
+    /// and returns it. The slot's command buffer is submitted via [`Self::submit`].
+    pub unsafe fn acquire_slot<Vulkan: VulkanContext>(
+        &mut self,
+        vulkan: &Vulkan,
+    ) -> LabelledVkResult<SubmissionSlot> {
+        let slot = self.slots[self.next_slot];
+
+        // Wait for the slot's previous submission
+        unsafe {
+            self.semaphore
+                .wait(vulkan, self.slot_values[slot.index], Duration::MAX)
+        }?;
+
+        unsafe {
+            vulkan
+                .device()
+                .reset_command_pool(slot.command_pool, vk::CommandPoolResetFlags::empty())
+        }
+        .map_err(|e| VkError::new(e, "vkResetCommandPool"))?;
+
+        self.next_slot = (self.next_slot + 1) % self.slots.len();
+
+        Ok(slot)
+    }
+
+    /// Submits the recorded command buffer of `slot` via [`TimelineSemaphore::submit`],
+    /// signalling the next timeline value, which is returned. If `wait_stage` is some, the
+    /// submission waits at that stage for the previous submission's value.
+    ///
+    /// The slot only waits for the returned value when it is next acquired if the submission
+    /// succeeds.
+    ///
+    /// # Safety
+    /// * `slot` **MUST** have been acquired from this ring and its command buffer **MUST** be in
+    ///   the executable state.
+    /// * `queue` **MUST** be from the ring's queue family.
+    pub unsafe fn submit<'m, Vulkan, Queue>(
+        &mut self,
+        vulkan: &Vulkan,
+        queue: Queue,
+        slot: &SubmissionSlot,
+        wait_stage: Option<vk::PipelineStageFlags>,
+        label: &str,
+    ) -> LabelledVkResult<u64>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    {
+        let value = unsafe {
+            self.semaphore.submit(
+                vulkan,
+                queue,
+                slice::from_ref(&slot.command_buffer),
+                wait_stage,
+                label,
+            )
+        }?;
+
+        self.slot_values[slot.index] = value;
+
+        Ok(value)
+    }
+
+    /// Waits for every submitted slot's submission to complete.
+    pub unsafe fn wait_idle<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) -> LabelledVkResult<()> {
+        unsafe {
+            self.semaphore
+                .wait(vulkan, self.semaphore.last_value(), Duration::MAX)
+        }?;
+
+        Ok(())
+    }
+
+    /// Waits for every submission to complete, then destroys the ring.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
+        if let Err(e) = unsafe { self.wait_idle(vulkan) } {
+            error!("Destroy SubmissionRing: Failed to wait for submissions: {e}");
+            return;
+        }
+
+        unsafe {
+            self.slots.iter().for_each(|slot| {
+                vulkan
+                    .device()
+                    .destroy_command_pool(slot.command_pool, VK_GLOBAL_ALLOCATOR.as_deref())
+            });
+
+            self.semaphore.destroy(vulkan);
+        }
+    }
+
+    /// Creates the command pool and command buffer of a slot.
+    unsafe fn create_slot<Vulkan: VulkanContext>(
+        vulkan: &Vulkan,
+        family_index: u32,
+        index: usize,
+        label: &str,
+    ) -> LabelledVkResult<SubmissionSlot> {
+        let command_pool = {
+            let create_info = vk::CommandPoolCreateInfo::default()
+                .flags(vk::CommandPoolCreateFlags::TRANSIENT)
+                .queue_family_index(family_index);
+
+            let command_pool = unsafe {
+                vulkan
+                    .device()
+                    .create_command_pool(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+            }
+            .map_err(|e| VkError::new(e, "vkCreateCommandPool"))?;
+
+            unsafe {
+                try_name(
+                    vulkan,
+                    command_pool,
+                    &format!("{label} Ring Command Pool {index}"),
+                )
+            };
+
+            command_pool
+        };
+
+        let command_buffer = {
+            let allocate_info = vk::CommandBufferAllocateInfo::default()
+                .command_buffer_count(1)
+                .command_pool(command_pool)
+                .level(vk::CommandBufferLevel::PRIMARY);
+
+            let command_buffer =
+                match unsafe { vulkan.device().allocate_command_buffers(&allocate_info) } {
+                    Ok(command_buffers) => command_buffers[0],
+                    Err(e) => {
+                        unsafe {
+                            vulkan
+                                .device()
+                                .destroy_command_pool(command_pool, VK_GLOBAL_ALLOCATOR.as_deref())
+                        };
+                        return Err(VkError::new(e, "vkAllocateCommandBuffers"));
+                    }
+                };
+
+            unsafe {
+                try_name(
+                    vulkan,
+                    command_buffer,
+                    &format!("{label} Ring Command Buffer {index}"),
+                )
+            };
+
+            command_buffer
+        };
+
+        Ok(SubmissionSlot {
+            command_pool,
+            command_buffer,
+            index,
+        })
+    }
+}
@@ -0,0 +1,61 @@
+use core::slice;
+
+use ash::vk;
+
+use crate::{LabelledVkResult, MaybeMutex, VkError, VulkanContext};
+
+/// Submits `command_buffer` to `queue`, waiting on the binary semaphores `binary_waits` at their
+/// stages, then signalling the binary semaphores `binary_signals` and the timeline semaphores
+/// `timeline_signals` with their values. `fence` may be `vk::Fence::null()`.
+///
+/// # Safety
+/// * The semaphores in `binary_waits` and `binary_signals` **MUST** be binary semaphores.
+/// * The semaphores in `timeline_signals` **MUST** be timeline semaphores.
+/// * Each value in `timeline_signals` **MUST** be greater than the current value of its semaphore
+///   and any pending signal operations.
+pub unsafe fn submit_mixed<'m, Vulkan, Queue>(
+    vulkan: &Vulkan,
+    queue: Queue,
+    command_buffer: vk::CommandBuffer,
+    binary_waits: &[(vk::Semaphore, vk::PipelineStageFlags)],
+    binary_signals: &[vk::Semaphore],
+    timeline_signals: &[(vk::Semaphore, u64)],
+    fence: vk::Fence,
+) -> LabelledVkResult<()>
+where
+    Vulkan: VulkanContext,
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+{
+    let (wait_semaphores, wait_stages): (Vec<_>, Vec<_>) = binary_waits.iter().copied().unzip();
+
+    // Binary semaphores ignore their value, but the value arrays must match the semaphore arrays.
+    let wait_values = vec![0; wait_semaphores.len()];
+
+    let (signal_semaphores, signal_values): (Vec<_>, Vec<_>) = binary_signals
+        .iter()
+        .map(|&semaphore| (semaphore, 0))
+        .chain(timeline_signals.iter().copied())
+        .unzip();
+
+    let mut timeline_info = vk::TimelineSemaphoreSubmitInfo::default()
+        .wait_semaphore_values(&wait_values)
+        .signal_semaphore_values(&signal_values);
+
+    let submit_info = vk::SubmitInfo::default()
+        .wait_semaphores(&wait_semaphores)
+        .wait_dst_stage_mask(&wait_stages)
+        .command_buffers(slice::from_ref(&command_buffer))
+        .signal_semaphores(&signal_semaphores)
+        .push_next(&mut timeline_info);
+
+    let (queue, _queue_guard) = queue.into().lock();
+
+    unsafe {
+        vulkan
+            .device()
+            .queue_submit(queue, slice::from_ref(&submit_info), fence)
+    }
+    .map_err(|e| VkError::new(e, "vkQueueSubmit"))?;
+
+    Ok(())
+}
@@ -7,6 +7,8 @@ use crate::{
     debug_utils::{queue_try_begin_label, queue_try_end_label, try_name},
 };
 
+use super::record_command_buffer;
+
 /// Creates the resources to run a onetime command, waits for completion, then cleans up. Useful for
 /// copies during setup. Works best when `command_pool` was created with the
 /// `vk::CommandPoolCreateFlags::TRANSIENT`.
@@ -27,33 +29,277 @@ where
     let (pool, pool_guard) = maybe_mutex_pool.lock();
 
     // Allocate command buffer
-    let command_buffer = {
-        let allocate_info = vk::CommandBufferAllocateInfo::default()
-            .command_pool(pool)
-            .level(vk::CommandBufferLevel::PRIMARY)
-            .command_buffer_count(1);
-
-        unsafe { vulkan.device().allocate_command_buffers(&allocate_info) }
-            .map_err(|e| VkError::new(e, "vkAllocateCommandBuffers"))?[0]
+    let command_buffer = unsafe { allocate_command_buffer(vulkan, pool) }?;
+
+    // Recording
+    unsafe { record_onetime(vulkan, command_buffer, cmd_fn) }?;
+
+    // Submit
+    let fence = unsafe { submit_with_fence(vulkan, command_buffer, queue, label) }?;
+
+    #[allow(unused)]
+    let pool = (); // Shadow pool to prevent usage after guard drop.
+    drop(pool_guard);
+
+    // Wait for submission to complete
+    unsafe { wait_and_destroy_fence(vulkan, fence) }?;
+
+    // Cleanup
+    unsafe {
+        let (pool, _pool_guard) = maybe_mutex_pool.lock();
+        vulkan
+            .device()
+            .free_command_buffers(pool, slice::from_ref(&command_buffer))
+    };
+
+    Ok(())
+}
+
+/// Runs a onetime command like [`onetime_command`], but reuses the command buffer stored in
+/// `cached_command_buffer`. The command buffer is allocated on the first call and reset on
+/// subsequent calls. Freeing the command buffer is left to the caller.
+///
+/// # Safety
+/// * `command_pool` **MUST** have been created with
+///   `vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER`.
+/// * `cached_command_buffer` **MUST** be `None` or a command buffer allocated from `command_pool`.
+pub unsafe fn onetime_command_reuse<'m, Vulkan, CmdFn, Queue, Pool>(
+    vulkan: &Vulkan,
+    command_pool: Pool,
+    queue: Queue,
+    cached_command_buffer: &mut Option<vk::CommandBuffer>,
+    cmd_fn: CmdFn,
+    label: &str,
+) -> LabelledVkResult<()>
+where
+    Vulkan: VulkanContext,
+    CmdFn: FnOnce(&Vulkan, vk::CommandBuffer),
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    Pool: Into<MaybeMutex<'m, vk::CommandPool>>,
+{
+    let (pool, pool_guard) = command_pool.into().lock();
+
+    // Allocate or reset the command buffer
+    let command_buffer = match *cached_command_buffer {
+        Some(command_buffer) => {
+            unsafe {
+                vulkan
+                    .device()
+                    .reset_command_buffer(command_buffer, vk::CommandBufferResetFlags::empty())
+            }
+            .map_err(|e| VkError::new(e, "vkResetCommandBuffer"))?;
+
+            command_buffer
+        }
+
+        None => {
+            let command_buffer = unsafe { allocate_command_buffer(vulkan, pool) }?;
+            *cached_command_buffer = Some(command_buffer);
+
+            command_buffer
+        }
     };
 
     // Recording
+    unsafe { record_onetime(vulkan, command_buffer, cmd_fn) }?;
+
+    // Submit
+    let fence = unsafe { submit_with_fence(vulkan, command_buffer, queue, label) }?;
+
+    #[allow(unused)]
+    let pool = (); // Shadow pool to prevent usage after guard drop.
+    drop(pool_guard);
+
+    // Wait for submission to complete
+    unsafe { wait_and_destroy_fence(vulkan, fence) }?;
+
+    Ok(())
+}
+
+/// Runs a onetime command like [`onetime_command`], but signals `semaphore` with `signal_value`
+/// on completion and waits on that value instead of a fence.
+///
+/// # Safety
+/// * `semaphore` **MUST** be a timeline semaphore.
+/// * `signal_value` **MUST** be greater than the current value of `semaphore` and any pending
+///   signal operations.
+pub unsafe fn onetime_command_timeline<'m, Vulkan, CmdFn, Queue, Pool>(
+    vulkan: &Vulkan,
+    command_pool: Pool,
+    queue: Queue,
+    semaphore: vk::Semaphore,
+    signal_value: u64,
+    cmd_fn: CmdFn,
+    label: &str,
+) -> LabelledVkResult<()>
+where
+    Vulkan: VulkanContext,
+    CmdFn: FnOnce(&Vulkan, vk::CommandBuffer),
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    Pool: Into<MaybeMutex<'m, vk::CommandPool>>,
+{
+    let maybe_mutex_pool = command_pool.into();
+    let (pool, pool_guard) = maybe_mutex_pool.lock();
+
+    // Allocate command buffer
+    let command_buffer = unsafe { allocate_command_buffer(vulkan, pool) }?;
+
+    // Recording
+    unsafe { record_onetime(vulkan, command_buffer, cmd_fn) }?;
+
+    // Submit
     {
-        let begin_info = vk::CommandBufferBeginInfo::default()
-            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+        let mut timeline_info = vk::TimelineSemaphoreSubmitInfo::default()
+            .signal_semaphore_values(slice::from_ref(&signal_value));
+
+        let submit_info = vk::SubmitInfo::default()
+            .command_buffers(slice::from_ref(&command_buffer))
+            .signal_semaphores(slice::from_ref(&semaphore))
+            .push_next(&mut timeline_info);
+
+        let (queue, _queue_guard) = queue.into().lock();
+
+        unsafe { queue_try_begin_label(vulkan, queue, label) };
+
         unsafe {
             vulkan
                 .device()
-                .begin_command_buffer(command_buffer, &begin_info)
-                .map_err(|e| VkError::new(e, "vkBeginCommandBuffer"))?;
+                .queue_submit(queue, slice::from_ref(&submit_info), vk::Fence::null())
+                .map_err(|e| VkError::new(e, "vkQueueSubmit"))?;
+        }
+
+        unsafe { queue_try_end_label(vulkan, queue) };
+    }
+
+    #[allow(unused)]
+    let pool = (); // Shadow pool to prevent usage after guard drop.
+    drop(pool_guard);
+
+    // Wait for submission to complete
+    {
+        let wait_info = vk::SemaphoreWaitInfo::default()
+            .semaphores(slice::from_ref(&semaphore))
+            .values(slice::from_ref(&signal_value));
+
+        unsafe { vulkan.device().wait_semaphores(&wait_info, u64::MAX) }
+            .map_err(|e| VkError::new(e, "vkWaitSemaphores"))?;
+    }
+
+    // Cleanup
+    unsafe {
+        let (pool, _pool_guard) = maybe_mutex_pool.lock();
+        vulkan
+            .device()
+            .free_command_buffers(pool, slice::from_ref(&command_buffer))
+    };
+
+    Ok(())
+}
+
+/// Creates a `TRANSIENT` command pool for `family_index`, runs `pool_fn` with it, waits for `queue`
+/// to be idle, then destroys the pool. Useful for setup work that needs several command buffers.
+///
+/// # Safety
+/// * `queue` **MUST** be from `family_index`.
+/// * All work using command buffers from the pool **MUST** be submitted to `queue`.
+pub unsafe fn with_transient_pool<'m, Vulkan, PoolFn, Queue, R>(
+    vulkan: &Vulkan,
+    family_index: u32,
+    queue: Queue,
+    pool_fn: PoolFn,
+    label: &str,
+) -> LabelledVkResult<R>
+where
+    Vulkan: VulkanContext,
+    PoolFn: FnOnce(vk::CommandPool) -> R,
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+{
+    // Create the pool
+    let pool = {
+        let create_info = vk::CommandPoolCreateInfo::default()
+            .flags(vk::CommandPoolCreateFlags::TRANSIENT)
+            .queue_family_index(family_index);
+
+        let pool = unsafe {
+            vulkan
+                .device()
+                .create_command_pool(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
         }
+        .map_err(|e| VkError::new(e, "vkCreateCommandPool"))?;
+
+        unsafe { try_name(vulkan, pool, &format!("{label} Transient Command Pool")) };
+
+        pool
+    };
 
-        cmd_fn(vulkan, command_buffer);
+    let output = pool_fn(pool);
 
-        unsafe { vulkan.device().end_command_buffer(command_buffer) }
-            .map_err(|e| VkError::new(e, "vkEndCommandBuffer"))?;
+    // Wait for the work to complete
+    let wait_result = {
+        let (queue, _queue_guard) = queue.into().lock();
+        unsafe { vulkan.device().queue_wait_idle(queue) }
+            .map_err(|e| VkError::new(e, "vkQueueWaitIdle"))
+    };
+
+    // The pool is destroyed even if the wait failed, as the device may be lost.
+    unsafe {
+        vulkan
+            .device()
+            .destroy_command_pool(pool, VK_GLOBAL_ALLOCATOR.as_deref())
+    };
+
+    wait_result?;
+
+    Ok(output)
+}
+
+/// Allocates a single primary command buffer from `pool`.
+unsafe fn allocate_command_buffer<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    pool: vk::CommandPool,
+) -> LabelledVkResult<vk::CommandBuffer> {
+    let allocate_info = vk::CommandBufferAllocateInfo::default()
+        .command_pool(pool)
+        .level(vk::CommandBufferLevel::PRIMARY)
+        .command_buffer_count(1);
+
+    let command_buffer = unsafe { vulkan.device().allocate_command_buffers(&allocate_info) }
+        .map_err(|e| VkError::new(e, "vkAllocateCommandBuffers"))?[0];
+
+    Ok(command_buffer)
+}
+
+/// Records `cmd_fn` into `command_buffer` for a single submission.
+unsafe fn record_onetime<Vulkan, CmdFn>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+    cmd_fn: CmdFn,
+) -> LabelledVkResult<()>
+where
+    Vulkan: VulkanContext,
+    CmdFn: FnOnce(&Vulkan, vk::CommandBuffer),
+{
+    unsafe {
+        record_command_buffer(
+            vulkan,
+            command_buffer,
+            vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT,
+            cmd_fn,
+        )
     }
+}
 
+/// Submits `command_buffer` to `queue`, returning the fence that signals on completion.
+unsafe fn submit_with_fence<'m, Vulkan, Queue>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+    queue: Queue,
+    label: &str,
+) -> LabelledVkResult<vk::Fence>
+where
+    Vulkan: VulkanContext,
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+{
     // Create fence
     let fence = {
         let fence_info = vk::FenceCreateInfo::default();
@@ -89,11 +335,14 @@ where
         unsafe { queue_try_end_label(vulkan, queue) };
     }
 
-    #[allow(unused)]
-    let pool = (); // Shadow pool to prevent usage after guard drop.
-    drop(pool_guard);
+    Ok(fence)
+}
 
-    // Wait for submission to complete
+/// Waits for `fence` to signal, then destroys it.
+unsafe fn wait_and_destroy_fence<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    fence: vk::Fence,
+) -> LabelledVkResult<()> {
     unsafe {
         vulkan
             .device()
@@ -101,16 +350,10 @@ where
             .map_err(|e| VkError::new(e, "vkWaitForFences"))?;
     }
 
-    // Cleanup
     unsafe {
         vulkan
             .device()
-            .destroy_fence(fence, VK_GLOBAL_ALLOCATOR.as_deref());
-
-        let (pool, _pool_guard) = maybe_mutex_pool.lock();
-        vulkan
-            .device()
-            .free_command_buffers(pool, slice::from_ref(&command_buffer))
+            .destroy_fence(fence, VK_GLOBAL_ALLOCATOR.as_deref())
     };
 
     Ok(())
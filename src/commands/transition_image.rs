@@ -57,7 +57,87 @@ pub unsafe fn cmd_transition_image<Vulkan: VulkanContext>(
     Some(())
 }
 
-fn pipeline_stage_access_tuple(
+/// Transitions an image between layouts while transferring ownership between queue families, as a
+/// single `vk::ImageMemoryBarrier2`. Requires `synchronization2`.
+///
+/// The barrier **MUST** be recorded twice, once with `is_release` on a queue from `src_family`
+/// then without `is_release` on a queue from `dst_family`, with the same layouts and families.
+///
+/// Supports the same layouts as [`cmd_transition_image`].
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn cmd_transition_and_transfer<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    command_buffer: vk::CommandBuffer,
+    image: vk::Image,
+    old_layout: vk::ImageLayout,
+    new_layout: vk::ImageLayout,
+    src_family: u32,
+    dst_family: u32,
+    is_release: bool,
+) -> Option<()> {
+    let (src_stage, src_access) = pipeline_stage_access_tuple(old_layout)?;
+    let (dst_stage, dst_access) = pipeline_stage_access_tuple(new_layout)?;
+
+    let src_stage = vk::PipelineStageFlags2::from_raw(u64::from(src_stage.as_raw()));
+    let src_access = vk::AccessFlags2::from_raw(u64::from(src_access.as_raw()));
+    let dst_stage = vk::PipelineStageFlags2::from_raw(u64::from(dst_stage.as_raw()));
+    let dst_access = vk::AccessFlags2::from_raw(u64::from(dst_access.as_raw()));
+
+    // The release only makes the source accesses available, the acquire only makes them visible
+    // to the destination accesses.
+    let (src_stage, src_access, dst_stage, dst_access) = if is_release {
+        (
+            src_stage,
+            src_access,
+            vk::PipelineStageFlags2::NONE,
+            vk::AccessFlags2::NONE,
+        )
+    } else {
+        (
+            vk::PipelineStageFlags2::NONE,
+            vk::AccessFlags2::NONE,
+            dst_stage,
+            dst_access,
+        )
+    };
+
+    let image_barrier = vk::ImageMemoryBarrier2::default()
+        .old_layout(old_layout)
+        .src_stage_mask(src_stage)
+        .src_access_mask(src_access)
+        .src_queue_family_index(src_family)
+        .new_layout(new_layout)
+        .dst_stage_mask(dst_stage)
+        .dst_access_mask(dst_access)
+        .dst_queue_family_index(dst_family)
+        .image(image)
+        .subresource_range(
+            vk::ImageSubresourceRange::default()
+                .aspect_mask(vk::ImageAspectFlags::COLOR)
+                .base_array_layer(0)
+                .base_mip_level(0)
+                .layer_count(1)
+                .level_count(1),
+        );
+
+    let dependency_info =
+        vk::DependencyInfo::default().image_memory_barriers(slice::from_ref(&image_barrier));
+
+    unsafe {
+        vulkan
+            .device()
+            .cmd_pipeline_barrier2(command_buffer, &dependency_info)
+    };
+
+    Some(())
+}
+
+/// Returns if `layout` is supported by [`cmd_transition_image`].
+pub(crate) fn is_supported_layout(layout: vk::ImageLayout) -> bool {
+    pipeline_stage_access_tuple(layout).is_some()
+}
+
+pub(super) fn pipeline_stage_access_tuple(
     layout: vk::ImageLayout,
 ) -> Option<(vk::PipelineStageFlags, vk::AccessFlags)> {
     let stage = match layout {
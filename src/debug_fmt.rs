@@ -0,0 +1,103 @@
+use ash::vk;
+
+/// Formats queue flags as their names separated by `|`. E.g., `GRAPHICS | COMPUTE`.
+pub fn format_queue_flags(flags: vk::QueueFlags) -> String {
+    format_flags(
+        flags.as_raw(),
+        &[
+            (vk::QueueFlags::GRAPHICS, "GRAPHICS"),
+            (vk::QueueFlags::COMPUTE, "COMPUTE"),
+            (vk::QueueFlags::TRANSFER, "TRANSFER"),
+            (vk::QueueFlags::SPARSE_BINDING, "SPARSE_BINDING"),
+            (vk::QueueFlags::PROTECTED, "PROTECTED"),
+            (vk::QueueFlags::VIDEO_DECODE_KHR, "VIDEO_DECODE_KHR"),
+            (vk::QueueFlags::VIDEO_ENCODE_KHR, "VIDEO_ENCODE_KHR"),
+            (vk::QueueFlags::OPTICAL_FLOW_NV, "OPTICAL_FLOW_NV"),
+        ]
+        .map(|(flag, name)| (flag.as_raw(), name)),
+    )
+}
+
+/// Formats memory property flags as their names separated by `|`. E.g.,
+/// `HOST_VISIBLE | HOST_COHERENT`.
+pub fn format_memory_property_flags(flags: vk::MemoryPropertyFlags) -> String {
+    format_flags(
+        flags.as_raw(),
+        &[
+            (vk::MemoryPropertyFlags::DEVICE_LOCAL, "DEVICE_LOCAL"),
+            (vk::MemoryPropertyFlags::HOST_VISIBLE, "HOST_VISIBLE"),
+            (vk::MemoryPropertyFlags::HOST_COHERENT, "HOST_COHERENT"),
+            (vk::MemoryPropertyFlags::HOST_CACHED, "HOST_CACHED"),
+            (
+                vk::MemoryPropertyFlags::LAZILY_ALLOCATED,
+                "LAZILY_ALLOCATED",
+            ),
+            (vk::MemoryPropertyFlags::PROTECTED, "PROTECTED"),
+            (
+                vk::MemoryPropertyFlags::DEVICE_COHERENT_AMD,
+                "DEVICE_COHERENT_AMD",
+            ),
+            (
+                vk::MemoryPropertyFlags::DEVICE_UNCACHED_AMD,
+                "DEVICE_UNCACHED_AMD",
+            ),
+            (vk::MemoryPropertyFlags::RDMA_CAPABLE_NV, "RDMA_CAPABLE_NV"),
+        ]
+        .map(|(flag, name)| (flag.as_raw(), name)),
+    )
+}
+
+/// Formats image usage flags as their names separated by `|`. E.g., `TRANSFER_DST | SAMPLED`.
+pub fn format_image_usage_flags(flags: vk::ImageUsageFlags) -> String {
+    format_flags(
+        flags.as_raw(),
+        &[
+            (vk::ImageUsageFlags::TRANSFER_SRC, "TRANSFER_SRC"),
+            (vk::ImageUsageFlags::TRANSFER_DST, "TRANSFER_DST"),
+            (vk::ImageUsageFlags::SAMPLED, "SAMPLED"),
+            (vk::ImageUsageFlags::STORAGE, "STORAGE"),
+            (vk::ImageUsageFlags::COLOR_ATTACHMENT, "COLOR_ATTACHMENT"),
+            (
+                vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+                "DEPTH_STENCIL_ATTACHMENT",
+            ),
+            (
+                vk::ImageUsageFlags::TRANSIENT_ATTACHMENT,
+                "TRANSIENT_ATTACHMENT",
+            ),
+            (vk::ImageUsageFlags::INPUT_ATTACHMENT, "INPUT_ATTACHMENT"),
+            (
+                vk::ImageUsageFlags::FRAGMENT_SHADING_RATE_ATTACHMENT_KHR,
+                "FRAGMENT_SHADING_RATE_ATTACHMENT_KHR",
+            ),
+            (
+                vk::ImageUsageFlags::FRAGMENT_DENSITY_MAP_EXT,
+                "FRAGMENT_DENSITY_MAP_EXT",
+            ),
+        ]
+        .map(|(flag, name)| (flag.as_raw(), name)),
+    )
+}
+
+/// Formats the set bits in `raw` using the names in `names`, any unnamed bits are formatted as hex.
+fn format_flags(raw: vk::Flags, names: &[(vk::Flags, &str)]) -> String {
+    if raw == 0 {
+        return String::from("NONE");
+    }
+
+    let mut unnamed = raw;
+    let mut parts: Vec<String> = names
+        .iter()
+        .filter(|(flag, _)| raw & flag == *flag)
+        .map(|(flag, name)| {
+            unnamed &= !flag;
+            String::from(*name)
+        })
+        .collect();
+
+    if unnamed != 0 {
+        parts.push(format!("{unnamed:#x}"));
+    }
+
+    parts.join(" | ")
+}
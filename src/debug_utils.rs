@@ -1,9 +1,12 @@
-use core::ffi::CStr;
+use core::ffi::{CStr, c_void};
 
 use ash::{ext, vk};
-use tracing::{debug, error, info, warn};
 
-use crate::{LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext};
+use crate::{
+    LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext,
+    logging::{debug, error, info, warn},
+    maybe_mutex::{Mutex, lock_mutex},
+};
 
 /// https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkSetDebugUtilsObjectNameEXT.html
 pub unsafe fn try_name<Vulkan, H>(vulkan: &Vulkan, handle: H, name: &str)
@@ -134,10 +137,22 @@ pub struct DebugUtils {
     /// The Debug Utils Messenger.
     pub messenger: vk::DebugUtilsMessengerEXT,
 
+    /// The messengers added with [`DebugUtils::add_messenger`].
+    pub additional_messengers: Vec<vk::DebugUtilsMessengerEXT>,
+
     /// The Debug Utils Device.
     pub device: ext::debug_utils::Device,
+
+    /// The closure receiving messages, if created with [`DebugUtils::new_with_closure`]. The
+    /// messenger's user data points into this box, so it is only dropped by
+    /// [`DebugUtils::destroy`] once the messenger has been destroyed.
+    closure: Option<Box<DebugClosure>>,
 }
 
+/// A closure that receives debug messages and their severity.
+type DebugClosure =
+    Mutex<Box<dyn FnMut(&DebugMessage<'_>, vk::DebugUtilsMessageSeverityFlagsEXT) + Send>>;
+
 impl DebugUtils {
     /// Registers Vulkan's debug utils and messenger to receive [`log`] messages from any Vulkan
     /// debug calls.
@@ -146,6 +161,56 @@ impl DebugUtils {
         vk_instance: &ash::Instance,
         vk_device: &ash::Device,
         message_callback: vk::PFN_vkDebugUtilsMessengerCallbackEXT,
+    ) -> LabelledVkResult<Self> {
+        unsafe {
+            Self::create(
+                entry,
+                vk_instance,
+                vk_device,
+                message_callback,
+                core::ptr::null_mut(),
+                None,
+            )
+        }
+    }
+
+    /// Registers Vulkan's debug utils and messenger to forward messages to `closure`. The closure
+    /// may be called from any thread that makes Vulkan calls.
+    ///
+    /// # Safety
+    /// * [`DebugUtils::destroy`] **MUST** be called before this is dropped.
+    pub unsafe fn new_with_closure<Closure>(
+        entry: &ash::Entry,
+        vk_instance: &ash::Instance,
+        vk_device: &ash::Device,
+        closure: Closure,
+    ) -> LabelledVkResult<Self>
+    where
+        Closure: FnMut(&DebugMessage<'_>, vk::DebugUtilsMessageSeverityFlagsEXT) + Send + 'static,
+    {
+        let closure: Box<DebugClosure> = Box::new(Mutex::new(Box::new(closure)));
+        let user_data = (&raw const *closure).cast_mut().cast::<c_void>();
+
+        unsafe {
+            Self::create(
+                entry,
+                vk_instance,
+                vk_device,
+                Some(closure_trampoline),
+                user_data,
+                Some(closure),
+            )
+        }
+    }
+
+    /// Creates the messenger with the callback and user data.
+    unsafe fn create(
+        entry: &ash::Entry,
+        vk_instance: &ash::Instance,
+        vk_device: &ash::Device,
+        message_callback: vk::PFN_vkDebugUtilsMessengerCallbackEXT,
+        user_data: *mut c_void,
+        closure: Option<Box<DebugClosure>>,
     ) -> LabelledVkResult<Self> {
         let debug_info = vk::DebugUtilsMessengerCreateInfoEXT::default()
             .message_severity(
@@ -159,7 +224,8 @@ impl DebugUtils {
                     | vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION
                     | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE,
             )
-            .pfn_user_callback(message_callback);
+            .pfn_user_callback(message_callback)
+            .user_data(user_data);
 
         let instance = ext::debug_utils::Instance::new(entry, vk_instance);
 
@@ -173,9 +239,57 @@ impl DebugUtils {
         Ok(Self {
             instance,
             messenger,
+            additional_messengers: vec![],
             device,
+            closure,
         })
     }
+
+    /// Registers an additional messenger that only receives messages matching `severity` and
+    /// `message_type`, e.g., only `PERFORMANCE` warnings and errors. The messenger is destroyed by
+    /// [`DebugUtils::destroy`].
+    pub unsafe fn add_messenger(
+        &mut self,
+        severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+        message_type: vk::DebugUtilsMessageTypeFlagsEXT,
+        message_callback: vk::PFN_vkDebugUtilsMessengerCallbackEXT,
+    ) -> LabelledVkResult<vk::DebugUtilsMessengerEXT> {
+        let debug_info = vk::DebugUtilsMessengerCreateInfoEXT::default()
+            .message_severity(severity)
+            .message_type(message_type)
+            .pfn_user_callback(message_callback);
+
+        let messenger = unsafe {
+            self.instance
+                .create_debug_utils_messenger(&debug_info, VK_GLOBAL_ALLOCATOR.as_deref())
+        }
+        .map_err(|e| VkError::new(e, "vkCreateDebugUtilsMessengerEXT"))?;
+
+        self.additional_messengers.push(messenger);
+
+        Ok(messenger)
+    }
+
+    /// Destroys the debug messengers, then drops the closure if there is one.
+    ///
+    /// # Safety
+    /// * This **MUST** be called before the Vulkan instance is destroyed.
+    pub unsafe fn destroy(&mut self) {
+        for &messenger in &self.additional_messengers {
+            unsafe {
+                self.instance
+                    .destroy_debug_utils_messenger(messenger, VK_GLOBAL_ALLOCATOR.as_deref())
+            };
+        }
+
+        unsafe {
+            self.instance
+                .destroy_debug_utils_messenger(self.messenger, VK_GLOBAL_ALLOCATOR.as_deref())
+        };
+
+        // The messenger no longer references the closure.
+        self.closure = None;
+    }
 }
 
 /// Represents the data from a `vk::DebugUtilsMessengerCallbackDataEXT` with nice display.
@@ -336,6 +450,28 @@ impl core::fmt::Display for DebugMessage<'_> {
     }
 }
 
+/// Messenger that forwards messages to the `DebugClosure` in the user data.
+unsafe extern "system" fn closure_trampoline(
+    message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+    message_type: vk::DebugUtilsMessageTypeFlagsEXT,
+    p_callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT<'_>,
+    user_data: *mut c_void,
+) -> vk::Bool32 {
+    if user_data.is_null() {
+        return vk::FALSE;
+    }
+
+    let Some(message) = (unsafe { DebugMessage::try_from(p_callback_data, message_type) }) else {
+        return vk::FALSE;
+    };
+
+    let closure = unsafe { &*user_data.cast::<DebugClosure>() };
+    let mut closure = lock_mutex(closure);
+    (*closure)(&message, message_severity);
+
+    vk::FALSE
+}
+
 /// Default messenger for Debug Utils.
 pub unsafe extern "system" fn vulkan_debug_callback(
     message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
@@ -0,0 +1,88 @@
+use core::slice;
+
+use ash::vk;
+
+use crate::{LabelledVkResult, VkError, VulkanContext, fences_are_signaled, logging::error};
+
+/// A deferred destruction of a resource.
+pub struct Deletion<Vulkan> {
+    /// The fence that signals once the resource is no longer in use.
+    pub fence: vk::Fence,
+    /// The function that destroys the resource.
+    pub destroy_fn: Box<dyn FnOnce(&Vulkan)>,
+}
+
+/// Defers destroying resources until the fence for the work that uses them has signalled.
+pub struct DeletionQueue<Vulkan> {
+    /// The deletions waiting for their fence to signal.
+    pub pending: Vec<Deletion<Vulkan>>,
+}
+
+impl<Vulkan: VulkanContext> DeletionQueue<Vulkan> {
+    #[allow(clippy::new_without_default)]
+    /// Create a new instance of Self.
+    pub fn new() -> Self {
+        Self { pending: vec![] }
+    }
+
+    /// Queue `resource` to be destroyed by `destroy_fn` once `fence` has signalled.
+    ///
+    /// # Safety
+    /// * `fence` **MUST** be signalled by the last submission that uses `resource`.
+    /// * `fence` **MUST NOT** be reset or destroyed until the deletion has been flushed.
+    pub unsafe fn queue<Resource, DestroyFn>(
+        &mut self,
+        fence: vk::Fence,
+        resource: Resource,
+        destroy_fn: DestroyFn,
+    ) where
+        Resource: 'static,
+        DestroyFn: FnOnce(&Vulkan, Resource) + 'static,
+    {
+        let deletion = Deletion {
+            fence,
+            destroy_fn: Box::new(move |vulkan: &Vulkan| destroy_fn(vulkan, resource)),
+        };
+
+        self.pending.push(deletion);
+    }
+
+    /// Destroy the resources whose fence has signalled.
+    pub fn flush(&mut self, vulkan: &Vulkan) -> LabelledVkResult<()> {
+        let mut length = self.pending.len();
+        let mut index = 0;
+        while index < length {
+            let is_signaled = unsafe {
+                fences_are_signaled(vulkan, slice::from_ref(&self.pending[index].fence))
+                    .map_err(|e| VkError::new(e, "vkWaitForFences"))?
+            };
+
+            if is_signaled {
+                // The current index has been replaced with the last item, thus current index
+                // should not change.
+                let deletion = self.pending.swap_remove(index);
+                (deletion.destroy_fn)(vulkan);
+
+                // An item has been removed from the vec, thus the length should be decremented.
+                length -= 1;
+            } else {
+                // Move to the next item
+                index += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Waits for the device to be idle, then destroys all pending resources.
+    pub fn destroy(&mut self, vulkan: &Vulkan) {
+        if let Err(e) = unsafe { vulkan.device().device_wait_idle() } {
+            error!("Destroy DeletionQueue: Failed to wait for device idle: {e}");
+            return;
+        }
+
+        self.pending
+            .drain(..)
+            .for_each(|deletion| (deletion.destroy_fn)(vulkan));
+    }
+}
@@ -0,0 +1,631 @@
+use core::slice;
+use std::collections::HashMap;
+
+use ash::vk;
+
+use crate::{LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, try_name};
+
+/// Writes combined image samplers into a descriptor array at arbitrary indices with a single
+/// `vkUpdateDescriptorSets`. Each entry is `(array_index, image_view, sampler, image_layout)`.
+///
+/// # Safety
+/// * `binding` **MUST** be a `COMBINED_IMAGE_SAMPLER` binding in `descriptor_set`.
+/// * Each `array_index` **MUST** be less than the binding's descriptor count.
+/// * If `descriptor_set` is in use, `binding` **MUST** have been created with
+///   `vk::DescriptorBindingFlags::UPDATE_AFTER_BIND`.
+pub unsafe fn update_texture_array<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    descriptor_set: vk::DescriptorSet,
+    binding: u32,
+    entries: &[(u32, vk::ImageView, vk::Sampler, vk::ImageLayout)],
+) {
+    if entries.is_empty() {
+        return;
+    }
+
+    let image_infos: Vec<_> = entries
+        .iter()
+        .map(|&(_, image_view, sampler, image_layout)| {
+            vk::DescriptorImageInfo::default()
+                .image_view(image_view)
+                .sampler(sampler)
+                .image_layout(image_layout)
+        })
+        .collect();
+
+    let writes: Vec<_> = entries
+        .iter()
+        .zip(image_infos.iter())
+        .map(|(&(array_index, ..), image_info)| {
+            vk::WriteDescriptorSet::default()
+                .dst_set(descriptor_set)
+                .dst_binding(binding)
+                .dst_array_element(array_index)
+                .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+                .image_info(slice::from_ref(image_info))
+        })
+        .collect();
+
+    unsafe { vulkan.device().update_descriptor_sets(&writes, &[]) };
+}
+
+/// A copy of descriptors from one binding to another, starting at array element `0` of each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DescriptorCopy {
+    /// The set to copy from.
+    pub src_set: vk::DescriptorSet,
+    /// The binding to copy from.
+    pub src_binding: u32,
+    /// The set to copy to.
+    pub dst_set: vk::DescriptorSet,
+    /// The binding to copy to.
+    pub dst_binding: u32,
+    /// The number of descriptors to copy.
+    pub count: u32,
+}
+
+impl From<(vk::DescriptorSet, u32, vk::DescriptorSet, u32, u32)> for DescriptorCopy {
+    fn from(
+        (src_set, src_binding, dst_set, dst_binding, count): (
+            vk::DescriptorSet,
+            u32,
+            vk::DescriptorSet,
+            u32,
+            u32,
+        ),
+    ) -> Self {
+        Self {
+            src_set,
+            src_binding,
+            dst_set,
+            dst_binding,
+            count,
+        }
+    }
+}
+
+/// Performs the descriptor `writes` and `copies` in a single `vkUpdateDescriptorSets`.
+pub unsafe fn update_descriptor_sets<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    writes: &[vk::WriteDescriptorSet<'_>],
+    copies: &[DescriptorCopy],
+) {
+    let copies: Vec<_> = copies
+        .iter()
+        .map(|copy| {
+            vk::CopyDescriptorSet::default()
+                .src_set(copy.src_set)
+                .src_binding(copy.src_binding)
+                .src_array_element(0)
+                .dst_set(copy.dst_set)
+                .dst_binding(copy.dst_binding)
+                .dst_array_element(0)
+                .descriptor_count(copy.count)
+        })
+        .collect();
+
+    unsafe { vulkan.device().update_descriptor_sets(writes, &copies) };
+}
+
+/// A descriptor pool that creates a new backing pool when the current pools are exhausted.
+pub struct GrowableDescriptorPool {
+    /// The pool sizes each backing pool is created with.
+    pub pool_sizes: Vec<vk::DescriptorPoolSize>,
+    /// The maximum number of sets each backing pool is created with.
+    pub max_sets: u32,
+    /// The flags each backing pool is created with.
+    pub flags: vk::DescriptorPoolCreateFlags,
+
+    /// The pools that may have space for more allocations.
+    pub ready_pools: Vec<vk::DescriptorPool>,
+    /// The pools that have been exhausted.
+    pub full_pools: Vec<vk::DescriptorPool>,
+
+    /// The number of pools that have been created.
+    pub pool_count: usize,
+
+    /// The label the backing pools are named with.
+    pub label: String,
+}
+
+impl GrowableDescriptorPool {
+    /// Create a new instance of Self, backing pools are created on demand with `pool_sizes`,
+    /// `max_sets`, and `flags`.
+    ///
+    /// Each backing pool is named `{label} Descriptor Pool {index}`.
+    pub fn new(
+        pool_sizes: &[vk::DescriptorPoolSize],
+        max_sets: u32,
+        flags: vk::DescriptorPoolCreateFlags,
+        label: &str,
+    ) -> Self {
+        Self {
+            pool_sizes: pool_sizes.to_vec(),
+            max_sets,
+            flags,
+            ready_pools: vec![],
+            full_pools: vec![],
+            pool_count: 0,
+            label: label.to_string(),
+        }
+    }
+
+    /// Allocates a descriptor set with `layout` named `{label} Descriptor Set`, creating a new
+    /// backing pool if the current pool is exhausted.
+    pub unsafe fn allocate<Vulkan: VulkanContext>(
+        &mut self,
+        vulkan: &Vulkan,
+        layout: vk::DescriptorSetLayout,
+        label: &str,
+    ) -> LabelledVkResult<vk::DescriptorSet> {
+        let mut pool = self.get_pool(vulkan)?;
+
+        let descriptor_set = match unsafe { Self::allocate_from(vulkan, pool, layout) } {
+            Err(vk::Result::ERROR_OUT_OF_POOL_MEMORY | vk::Result::ERROR_FRAGMENTED_POOL) => {
+                self.full_pools.push(pool);
+                pool = self.get_pool(vulkan)?;
+
+                unsafe { Self::allocate_from(vulkan, pool, layout) }
+            }
+
+            result => result,
+        };
+
+        self.ready_pools.push(pool);
+
+        let descriptor_set =
+            descriptor_set.map_err(|e| VkError::new(e, "vkAllocateDescriptorSets"))?;
+
+        unsafe { try_name(vulkan, descriptor_set, &format!("{label} Descriptor Set")) };
+
+        Ok(descriptor_set)
+    }
+
+    /// Resets all backing pools, freeing every descriptor set allocated from them.
+    ///
+    /// # Safety
+    /// * The descriptor sets allocated from this pool **MUST NOT** be in use.
+    pub unsafe fn reset<Vulkan: VulkanContext>(&mut self, vulkan: &Vulkan) -> LabelledVkResult<()> {
+        self.ready_pools.append(&mut self.full_pools);
+
+        for &pool in &self.ready_pools {
+            unsafe {
+                vulkan
+                    .device()
+                    .reset_descriptor_pool(pool, vk::DescriptorPoolResetFlags::empty())
+            }
+            .map_err(|e| VkError::new(e, "vkResetDescriptorPool"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Destroys all backing pools.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&mut self, vulkan: &Vulkan) {
+        self.ready_pools
+            .drain(..)
+            .chain(self.full_pools.drain(..))
+            .for_each(|pool| unsafe {
+                vulkan
+                    .device()
+                    .destroy_descriptor_pool(pool, VK_GLOBAL_ALLOCATOR.as_deref())
+            });
+    }
+
+    /// Get a ready pool else create a new one.
+    fn get_pool<Vulkan: VulkanContext>(
+        &mut self,
+        vulkan: &Vulkan,
+    ) -> LabelledVkResult<vk::DescriptorPool> {
+        if let Some(pool) = self.ready_pools.pop() {
+            return Ok(pool);
+        }
+
+        let create_info = vk::DescriptorPoolCreateInfo::default()
+            .flags(self.flags)
+            .max_sets(self.max_sets)
+            .pool_sizes(&self.pool_sizes);
+
+        let pool = unsafe {
+            vulkan
+                .device()
+                .create_descriptor_pool(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+        }
+        .map_err(|e| VkError::new(e, "vkCreateDescriptorPool"))?;
+
+        unsafe {
+            try_name(
+                vulkan,
+                pool,
+                &format!("{} Descriptor Pool {}", self.label, self.pool_count),
+            )
+        };
+
+        self.pool_count += 1;
+
+        Ok(pool)
+    }
+
+    /// Allocates a single descriptor set from `pool`.
+    unsafe fn allocate_from<Vulkan: VulkanContext>(
+        vulkan: &Vulkan,
+        pool: vk::DescriptorPool,
+        layout: vk::DescriptorSetLayout,
+    ) -> Result<vk::DescriptorSet, vk::Result> {
+        let allocate_info = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(pool)
+            .set_layouts(slice::from_ref(&layout));
+
+        let descriptor_set =
+            unsafe { vulkan.device().allocate_descriptor_sets(&allocate_info) }?[0];
+
+        Ok(descriptor_set)
+    }
+}
+
+/// A single binding of a descriptor set layout, used as the key of a [`DescriptorLayoutCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DescriptorBinding {
+    /// The binding number.
+    pub binding: u32,
+    /// The type of the descriptors in the binding.
+    pub descriptor_type: vk::DescriptorType,
+    /// The number of descriptors in the binding.
+    pub count: u32,
+    /// The shader stages that can access the binding.
+    pub stages: vk::ShaderStageFlags,
+}
+
+impl From<(u32, vk::DescriptorType, u32, vk::ShaderStageFlags)> for DescriptorBinding {
+    fn from(
+        (binding, descriptor_type, count, stages): (
+            u32,
+            vk::DescriptorType,
+            u32,
+            vk::ShaderStageFlags,
+        ),
+    ) -> Self {
+        Self {
+            binding,
+            descriptor_type,
+            count,
+            stages,
+        }
+    }
+}
+
+/// Builds a descriptor set layout binding by binding.
+#[derive(Debug, Clone, Default)]
+pub struct DescriptorSetLayoutBuilder {
+    /// The bindings of the layout.
+    pub bindings: Vec<DescriptorBinding>,
+    /// The flags of each binding.
+    pub binding_flags: Vec<vk::DescriptorBindingFlags>,
+    /// The flags the layout is created with.
+    pub flags: vk::DescriptorSetLayoutCreateFlags,
+}
+
+impl DescriptorSetLayoutBuilder {
+    /// Create a new builder with no bindings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a binding of `count` descriptors accessible from `stages`.
+    pub fn binding(
+        self,
+        binding: u32,
+        descriptor_type: vk::DescriptorType,
+        count: u32,
+        stages: vk::ShaderStageFlags,
+    ) -> Self {
+        self.binding_with_flags(
+            binding,
+            descriptor_type,
+            count,
+            stages,
+            vk::DescriptorBindingFlags::empty(),
+        )
+    }
+
+    /// Adds a binding like [`Self::binding`] with `binding_flags`, e.g., `PARTIALLY_BOUND` or
+    /// `UPDATE_AFTER_BIND`.
+    ///
+    /// `UPDATE_AFTER_BIND` requires the layout to be created with
+    /// `vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL`, see [`Self::flags`].
+    pub fn binding_with_flags(
+        mut self,
+        binding: u32,
+        descriptor_type: vk::DescriptorType,
+        count: u32,
+        stages: vk::ShaderStageFlags,
+        binding_flags: vk::DescriptorBindingFlags,
+    ) -> Self {
+        self.bindings.push(DescriptorBinding {
+            binding,
+            descriptor_type,
+            count,
+            stages,
+        });
+        self.binding_flags.push(binding_flags);
+        self
+    }
+
+    /// Sets the flags the layout is created with.
+    pub fn flags(mut self, flags: vk::DescriptorSetLayoutCreateFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Returns the pool sizes needed to allocate `set_count` sets with this layout, for creating a
+    /// [`GrowableDescriptorPool`].
+    pub fn pool_sizes(&self, set_count: u32) -> Vec<vk::DescriptorPoolSize> {
+        let mut pool_sizes: Vec<vk::DescriptorPoolSize> = vec![];
+
+        for binding in &self.bindings {
+            let count = binding.count * set_count;
+
+            match pool_sizes
+                .iter_mut()
+                .find(|pool_size| pool_size.ty == binding.descriptor_type)
+            {
+                Some(pool_size) => pool_size.descriptor_count += count,
+                None => pool_sizes.push(
+                    vk::DescriptorPoolSize::default()
+                        .ty(binding.descriptor_type)
+                        .descriptor_count(count),
+                ),
+            }
+        }
+
+        pool_sizes
+    }
+
+    /// Creates the layout.
+    pub unsafe fn build<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        label: &str,
+    ) -> LabelledVkResult<vk::DescriptorSetLayout> {
+        let layout_bindings: Vec<_> = self
+            .bindings
+            .iter()
+            .map(|binding| {
+                vk::DescriptorSetLayoutBinding::default()
+                    .binding(binding.binding)
+                    .descriptor_type(binding.descriptor_type)
+                    .descriptor_count(binding.count)
+                    .stage_flags(binding.stages)
+            })
+            .collect();
+
+        let mut binding_flags_info = vk::DescriptorSetLayoutBindingFlagsCreateInfo::default()
+            .binding_flags(&self.binding_flags);
+
+        let mut create_info = vk::DescriptorSetLayoutCreateInfo::default()
+            .flags(self.flags)
+            .bindings(&layout_bindings);
+
+        if self.binding_flags.iter().any(|flags| !flags.is_empty()) {
+            create_info = create_info.push_next(&mut binding_flags_info);
+        }
+
+        let layout = unsafe {
+            vulkan
+                .device()
+                .create_descriptor_set_layout(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+        }
+        .map_err(|e| VkError::new(e, "vkCreateDescriptorSetLayout"))?;
+
+        unsafe { try_name(vulkan, layout, &format!("{label} Descriptor Set Layout")) };
+
+        Ok(layout)
+    }
+}
+
+/// A cache of descriptor set layouts keyed on their bindings, so identical layouts are only
+/// created once.
+#[derive(Default)]
+pub struct DescriptorLayoutCache {
+    /// The cached layouts, keyed on their bindings sorted by binding number.
+    pub layouts: HashMap<Vec<DescriptorBinding>, vk::DescriptorSetLayout>,
+}
+
+impl DescriptorLayoutCache {
+    /// Create a new empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached layout for `bindings`, creating it if it does not exist. The order of
+    /// `bindings` does not affect the key.
+    pub unsafe fn get_or_create<Vulkan: VulkanContext>(
+        &mut self,
+        vulkan: &Vulkan,
+        bindings: &[DescriptorBinding],
+        label: &str,
+    ) -> LabelledVkResult<vk::DescriptorSetLayout> {
+        let mut key = bindings.to_vec();
+        key.sort_by_key(|binding| binding.binding);
+
+        if let Some(&layout) = self.layouts.get(&key) {
+            return Ok(layout);
+        }
+
+        let builder = key
+            .iter()
+            .fold(DescriptorSetLayoutBuilder::new(), |builder, binding| {
+                builder.binding(
+                    binding.binding,
+                    binding.descriptor_type,
+                    binding.count,
+                    binding.stages,
+                )
+            });
+
+        let layout = unsafe { builder.build(vulkan, label) }?;
+
+        self.layouts.insert(key, layout);
+
+        Ok(layout)
+    }
+
+    /// Destroys all cached layouts.
+    ///
+    /// # Safety
+    /// * The layouts **MUST NOT** be in use.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&mut self, vulkan: &Vulkan) {
+        self.layouts.drain().for_each(|(_, layout)| unsafe {
+            vulkan
+                .device()
+                .destroy_descriptor_set_layout(layout, VK_GLOBAL_ALLOCATOR.as_deref())
+        });
+    }
+}
+
+/// The descriptor infos of a pending write.
+#[derive(Debug, Clone)]
+enum DescriptorInfos {
+    Buffer(Vec<vk::DescriptorBufferInfo>),
+    Image(Vec<vk::DescriptorImageInfo>),
+}
+
+/// A pending write of a [`DescriptorWriter`].
+#[derive(Debug, Clone)]
+struct PendingWrite {
+    binding: u32,
+    array_element: u32,
+    descriptor_type: vk::DescriptorType,
+    infos: DescriptorInfos,
+}
+
+/// Collects a batch of descriptor writes that own their buffer and image infos, then performs
+/// them on a set in a single `vkUpdateDescriptorSets`.
+#[derive(Debug, Clone, Default)]
+pub struct DescriptorWriter {
+    writes: Vec<PendingWrite>,
+}
+
+impl DescriptorWriter {
+    /// Create a new empty writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes a single buffer descriptor to array element `0` of `binding`.
+    pub fn buffer(
+        self,
+        binding: u32,
+        descriptor_type: vk::DescriptorType,
+        buffer: vk::Buffer,
+        offset: vk::DeviceSize,
+        range: vk::DeviceSize,
+    ) -> Self {
+        let info = vk::DescriptorBufferInfo::default()
+            .buffer(buffer)
+            .offset(offset)
+            .range(range);
+
+        self.buffers(binding, 0, descriptor_type, &[info])
+    }
+
+    /// Writes buffer descriptors to consecutive array elements of `binding`, starting at
+    /// `array_element`.
+    pub fn buffers(
+        mut self,
+        binding: u32,
+        array_element: u32,
+        descriptor_type: vk::DescriptorType,
+        infos: &[vk::DescriptorBufferInfo],
+    ) -> Self {
+        self.writes.push(PendingWrite {
+            binding,
+            array_element,
+            descriptor_type,
+            infos: DescriptorInfos::Buffer(infos.to_vec()),
+        });
+        self
+    }
+
+    /// Writes a single image descriptor to array element `0` of `binding`.
+    pub fn image(
+        self,
+        binding: u32,
+        descriptor_type: vk::DescriptorType,
+        image_view: vk::ImageView,
+        sampler: vk::Sampler,
+        image_layout: vk::ImageLayout,
+    ) -> Self {
+        let info = vk::DescriptorImageInfo::default()
+            .image_view(image_view)
+            .sampler(sampler)
+            .image_layout(image_layout);
+
+        self.images(binding, 0, descriptor_type, &[info])
+    }
+
+    /// Writes image descriptors to consecutive array elements of `binding`, starting at
+    /// `array_element`.
+    pub fn images(
+        mut self,
+        binding: u32,
+        array_element: u32,
+        descriptor_type: vk::DescriptorType,
+        infos: &[vk::DescriptorImageInfo],
+    ) -> Self {
+        self.writes.push(PendingWrite {
+            binding,
+            array_element,
+            descriptor_type,
+            infos: DescriptorInfos::Image(infos.to_vec()),
+        });
+        self
+    }
+
+    /// Writes a single sampler descriptor to array element `0` of a `SAMPLER` binding.
+    pub fn sampler(self, binding: u32, sampler: vk::Sampler) -> Self {
+        let info = vk::DescriptorImageInfo::default().sampler(sampler);
+
+        self.images(binding, 0, vk::DescriptorType::SAMPLER, &[info])
+    }
+
+    /// Returns if the writer has no pending writes.
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// Performs every pending write on `descriptor_set`. The writer can be reused for other sets.
+    ///
+    /// # Safety
+    /// * Each write **MUST** match the type and count of its binding in `descriptor_set`'s layout.
+    /// * If `descriptor_set` is in use, each written binding **MUST** have been created with
+    ///   `vk::DescriptorBindingFlags::UPDATE_AFTER_BIND`.
+    pub unsafe fn update<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        descriptor_set: vk::DescriptorSet,
+    ) {
+        if self.writes.is_empty() {
+            return;
+        }
+
+        let writes: Vec<_> = self
+            .writes
+            .iter()
+            .map(|write| {
+                let write_info = vk::WriteDescriptorSet::default()
+                    .dst_set(descriptor_set)
+                    .dst_binding(write.binding)
+                    .dst_array_element(write.array_element)
+                    .descriptor_type(write.descriptor_type);
+
+                match &write.infos {
+                    DescriptorInfos::Buffer(infos) => write_info.buffer_info(infos),
+                    DescriptorInfos::Image(infos) => write_info.image_info(infos),
+                }
+            })
+            .collect();
+
+        unsafe { vulkan.device().update_descriptor_sets(&writes, &[]) };
+    }
+}
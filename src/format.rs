@@ -0,0 +1,53 @@
+use ash::vk;
+
+use crate::VulkanContext;
+
+/// Returns the properties of `format` on the physical device.
+pub fn format_properties<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    format: vk::Format,
+) -> vk::FormatProperties {
+    unsafe {
+        vulkan
+            .instance()
+            .get_physical_device_format_properties(vulkan.physical_device(), format)
+    }
+}
+
+/// Returns the tiling that supports `features` for `format`, preferring `OPTIMAL` over `LINEAR`.
+/// Returns `None` if neither tiling supports `features`.
+pub fn best_tiling_for<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    format: vk::Format,
+    features: vk::FormatFeatureFlags,
+) -> Option<vk::ImageTiling> {
+    let properties = format_properties(vulkan, format);
+
+    if properties.optimal_tiling_features.contains(features) {
+        Some(vk::ImageTiling::OPTIMAL)
+    } else if properties.linear_tiling_features.contains(features) {
+        Some(vk::ImageTiling::LINEAR)
+    } else {
+        None
+    }
+}
+
+/// Returns the aspects of `format`, `DEPTH` and/or `STENCIL` for depth/stencil formats, else
+/// `COLOR`.
+pub fn format_aspect_mask(format: vk::Format) -> vk::ImageAspectFlags {
+    match format {
+        vk::Format::D16_UNORM | vk::Format::X8_D24_UNORM_PACK32 | vk::Format::D32_SFLOAT => {
+            vk::ImageAspectFlags::DEPTH
+        }
+
+        vk::Format::S8_UINT => vk::ImageAspectFlags::STENCIL,
+
+        vk::Format::D16_UNORM_S8_UINT
+        | vk::Format::D24_UNORM_S8_UINT
+        | vk::Format::D32_SFLOAT_S8_UINT => {
+            vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL
+        }
+
+        _ => vk::ImageAspectFlags::COLOR,
+    }
+}
@@ -0,0 +1,112 @@
+use ash::vk;
+
+use crate::{LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, try_name};
+
+/// Creates a colour view of `cube_count` cubes in `image`, starting at array layer `0`. The view is
+/// `CUBE` if `cube_count` is `1`, else `CUBE_ARRAY`.
+///
+/// Returns `ERROR_FEATURE_NOT_PRESENT` if `cube_count` is greater than `1` and the device does
+/// not support `imageCubeArray`.
+///
+/// # Panics
+/// * If `cube_count` is `0`.
+///
+/// # Safety
+/// * `image` **MUST** have been created with `vk::ImageCreateFlags::CUBE_COMPATIBLE` and at least
+///   `6 * cube_count` array layers.
+/// * If `cube_count` is greater than `1`, the `imageCubeArray` feature **MUST** be enabled.
+pub unsafe fn create_cube_view<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    image: vk::Image,
+    format: vk::Format,
+    cube_count: u32,
+    label: &str,
+) -> LabelledVkResult<vk::ImageView> {
+    assert!(cube_count > 0, "A cube view must have at least one cube");
+
+    let view_type = if cube_count == 1 {
+        vk::ImageViewType::CUBE
+    } else {
+        let features = unsafe {
+            vulkan
+                .instance()
+                .get_physical_device_features(vulkan.physical_device())
+        };
+
+        if features.image_cube_array != vk::TRUE {
+            return Err(VkError::new(
+                vk::Result::ERROR_FEATURE_NOT_PRESENT,
+                "vkCreateImageView",
+            ));
+        }
+
+        vk::ImageViewType::CUBE_ARRAY
+    };
+
+    unsafe { create_view(vulkan, image, view_type, format, cube_count * 6, label) }
+}
+
+/// Creates a `TYPE_2D_ARRAY` colour view of the first `layer_count` array layers in `image`.
+///
+/// # Panics
+/// * If `layer_count` is `0`.
+///
+/// # Safety
+/// * `image` **MUST** have at least `layer_count` array layers.
+pub unsafe fn create_array_view<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    image: vk::Image,
+    format: vk::Format,
+    layer_count: u32,
+    label: &str,
+) -> LabelledVkResult<vk::ImageView> {
+    assert!(
+        layer_count > 0,
+        "An array view must have at least one layer"
+    );
+
+    unsafe {
+        create_view(
+            vulkan,
+            image,
+            vk::ImageViewType::TYPE_2D_ARRAY,
+            format,
+            layer_count,
+            label,
+        )
+    }
+}
+
+/// Creates a colour view of all mip levels and the first `layer_count` array layers in `image`.
+unsafe fn create_view<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    image: vk::Image,
+    view_type: vk::ImageViewType,
+    format: vk::Format,
+    layer_count: u32,
+    label: &str,
+) -> LabelledVkResult<vk::ImageView> {
+    let create_info = vk::ImageViewCreateInfo::default()
+        .image(image)
+        .view_type(view_type)
+        .format(format)
+        .subresource_range(
+            vk::ImageSubresourceRange::default()
+                .aspect_mask(vk::ImageAspectFlags::COLOR)
+                .base_mip_level(0)
+                .level_count(vk::REMAINING_MIP_LEVELS)
+                .base_array_layer(0)
+                .layer_count(layer_count),
+        );
+
+    let view = unsafe {
+        vulkan
+            .device()
+            .create_image_view(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+    }
+    .map_err(|e| VkError::new(e, "vkCreateImageView"))?;
+
+    unsafe { try_name(vulkan, view, &format!("{label} Image View")) };
+
+    Ok(view)
+}
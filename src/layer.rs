@@ -1,9 +1,13 @@
+use alloc::ffi::CString;
+use core::str::CharIndices;
 use std::{
     fs,
     io::{self, ErrorKind},
     path::Path,
 };
 
+use crate::logging::{debug, warn};
+
 /// A Vulkan layer to be initialized on application start.
 pub struct VulkanLayer {
     /// The file name of the layer's manifest.
@@ -33,6 +37,38 @@ impl VulkanLayer {
         }
     }
 
+    /// Parses the layer's name from the `layer.name` field of the layer's manifest. This is the name
+    /// to enable the layer with when creating the instance.
+    pub fn layer_name(&self) -> io::Result<CString> {
+        let invalid_data = |message: &str| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("{}: {message}", self.manifest_file_name),
+            )
+        };
+
+        let manifest = core::str::from_utf8(self.manifest)
+            .map_err(|_| invalid_data("manifest is not valid UTF-8"))?;
+
+        let layer = find_member(manifest, "layer")
+            .ok_or_else(|| invalid_data("manifest has no `layer` object"))?;
+
+        let (name, _) = find_member(layer, "name")
+            .and_then(parse_string)
+            .ok_or_else(|| invalid_data("manifest has no `layer.name` string"))?;
+
+        CString::new(name).map_err(|_| invalid_data("layer name contains a nul byte"))
+    }
+
+    /// Returns if the layer's binary is in the executable format of the current platform. Always
+    /// returns `true` if the current platform's format is unknown.
+    pub fn binary_matches_platform(&self) -> bool {
+        match BinaryFormat::native() {
+            Some(native) => BinaryFormat::identify(self.binary) == Some(native),
+            None => true,
+        }
+    }
+
     /// Writes the layers to the directory specified then sets the `VK_LAYER_PATH` accordingly.
     ///
     /// # Safety
@@ -67,20 +103,28 @@ impl VulkanLayer {
 
         // For each layer, write to their respective file
         for layer in layers {
+            if !layer.binary_matches_platform() {
+                warn!(
+                    "{} is not a {:?} binary and will likely fail to load",
+                    layer.binary_file_name,
+                    BinaryFormat::native()
+                );
+            }
+
             let manifest_path = directory.join(layer.manifest_file_name);
             if !manifest_path.try_exists()? || fs::read(&manifest_path)? != layer.manifest {
                 fs::write(manifest_path, layer.manifest)?;
-                tracing::debug!("Wrote {}", layer.manifest_file_name);
+                debug!("Wrote {}", layer.manifest_file_name);
             } else {
-                tracing::debug!("Skipped {}", layer.manifest_file_name);
+                debug!("Skipped {}", layer.manifest_file_name);
             }
 
             let binary_path = directory.join(layer.binary_file_name);
             if !binary_path.try_exists()? || fs::read(&binary_path)? != layer.binary {
                 fs::write(binary_path, layer.binary)?;
-                tracing::debug!("Wrote {}", layer.binary_file_name);
+                debug!("Wrote {}", layer.binary_file_name);
             } else {
-                tracing::debug!("Skipped {}", layer.binary_file_name);
+                debug!("Skipped {}", layer.binary_file_name);
             }
         }
 
@@ -104,3 +148,228 @@ impl VulkanLayer {
         Ok(())
     }
 }
+
+/// The executable format of a layer binary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryFormat {
+    /// Portable Executable, used by Windows.
+    Pe,
+    /// Executable and Linkable Format, used by Linux and most other Unix platforms.
+    Elf,
+    /// Mach-O, used by macOS and iOS.
+    MachO,
+}
+
+impl BinaryFormat {
+    /// Identifies the format of `binary` from its magic bytes.
+    pub fn identify(binary: &[u8]) -> Option<Self> {
+        match binary {
+            [0x4D, 0x5A, ..] => Some(Self::Pe),
+            [0x7F, 0x45, 0x4C, 0x46, ..] => Some(Self::Elf),
+            [0xFE, 0xED, 0xFA, 0xCE | 0xCF, ..]
+            | [0xCE | 0xCF, 0xFA, 0xED, 0xFE, ..]
+            | [0xCA, 0xFE, 0xBA, 0xBE, ..] => Some(Self::MachO),
+            _ => None,
+        }
+    }
+
+    /// The format of the current platform, if known.
+    pub fn native() -> Option<Self> {
+        if cfg!(target_os = "windows") {
+            Some(Self::Pe)
+        } else if cfg!(target_vendor = "apple") {
+            Some(Self::MachO)
+        } else if cfg!(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd"
+        )) {
+            Some(Self::Elf)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the remainder of the JSON `object` starting at the value of its top level member `key`.
+fn find_member<'a>(object: &'a str, key: &str) -> Option<&'a str> {
+    let mut rest = object.trim_start().strip_prefix('{')?;
+
+    loop {
+        let (member_key, after_key) = parse_string(rest.trim_start())?;
+        let value = after_key.trim_start().strip_prefix(':')?.trim_start();
+
+        if member_key == key {
+            return Some(value);
+        }
+
+        rest = skip_value(value)?.trim_start().strip_prefix(',')?;
+    }
+}
+
+/// Returns the remainder of the JSON `value` after skipping over it.
+fn skip_value(value: &str) -> Option<&str> {
+    if value.starts_with('"') {
+        return parse_string(value).map(|(_, rest)| rest);
+    }
+
+    if !value.starts_with(['{', '[']) {
+        let end = value.find([',', '}', ']']).unwrap_or(value.len());
+        return Some(&value[end..]);
+    }
+
+    let mut depth = 0usize;
+    let mut rest = value;
+    loop {
+        rest = &rest[rest.find(['"', '{', '[', '}', ']'])?..];
+
+        match rest.as_bytes()[0] {
+            b'"' => {
+                rest = parse_string(rest)?.1;
+                continue;
+            }
+            b'{' | b'[' => depth += 1,
+            _ => depth -= 1,
+        }
+
+        rest = &rest[1..];
+
+        if depth == 0 {
+            return Some(rest);
+        }
+    }
+}
+
+/// Parses the JSON string at the start of `input`, returning the string and the remainder.
+fn parse_string(input: &str) -> Option<(String, &str)> {
+    let body = input.strip_prefix('"')?;
+
+    let mut string = String::new();
+    let mut chars = body.char_indices();
+    while let Some((index, character)) = chars.next() {
+        match character {
+            '"' => return Some((string, &body[index + 1..])),
+
+            '\\' => {
+                let escaped = match chars.next()?.1 {
+                    '"' => '"',
+                    '\\' => '\\',
+                    '/' => '/',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'u' => {
+                        let code_unit = parse_hex_code_unit(&mut chars)?;
+
+                        // A high surrogate must be followed by an escaped low surrogate.
+                        let code_point = match code_unit {
+                            0xD800..=0xDBFF => {
+                                if chars.next()?.1 != '\\' || chars.next()?.1 != 'u' {
+                                    return None;
+                                }
+
+                                let low = parse_hex_code_unit(&mut chars)?;
+                                if !(0xDC00..=0xDFFF).contains(&low) {
+                                    return None;
+                                }
+
+                                0x10000 + ((code_unit - 0xD800) << 10) + (low - 0xDC00)
+                            }
+
+                            code_unit => code_unit,
+                        };
+
+                        // Lone low surrogates are rejected as they are not valid chars.
+                        char::from_u32(code_point)?
+                    }
+                    _ => return None,
+                };
+
+                string.push(escaped);
+            }
+
+            character => string.push(character),
+        }
+    }
+
+    None
+}
+
+/// Parses the four hex digits of a `\u` escape.
+fn parse_hex_code_unit(chars: &mut CharIndices<'_>) -> Option<u32> {
+    let hex: String = (0..4)
+        .map(|_| chars.next().map(|(_, character)| character))
+        .collect::<Option<_>>()?;
+
+    if !hex.chars().all(|character| character.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    u32::from_str_radix(&hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_member, parse_string, skip_value};
+
+    #[test]
+    fn parses_escapes() {
+        assert_eq!(
+            parse_string(r#""a\"b\\c\/d\n\t" rest"#),
+            Some(("a\"b\\c/d\n\t".to_owned(), " rest"))
+        );
+        assert_eq!(
+            parse_string(r#""\u0041\u00e9""#),
+            Some(("A\u{e9}".to_owned(), ""))
+        );
+    }
+
+    #[test]
+    fn parses_surrogate_pairs() {
+        assert_eq!(
+            parse_string(r#""\ud83d\ude00!""#),
+            Some(("\u{1f600}!".to_owned(), ""))
+        );
+        assert_eq!(
+            parse_string(r#""\uD834\uDD1E""#),
+            Some(("\u{1d11e}".to_owned(), ""))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_escapes() {
+        assert_eq!(parse_string(r#""\ud83d""#), None);
+        assert_eq!(parse_string(r#""\ud83dx""#), None);
+        assert_eq!(parse_string(r#""\ud83d\u0041""#), None);
+        assert_eq!(parse_string(r#""\ude00""#), None);
+        assert_eq!(parse_string(r#""\u+041""#), None);
+        assert_eq!(parse_string(r#""\q""#), None);
+        assert_eq!(parse_string(r#""unterminated"#), None);
+    }
+
+    #[test]
+    fn finds_members() {
+        let manifest = r#"{
+            "file_format_version": "1.2.0",
+            "other": { "name": "wrong", "list": ["}", 1, { "a": [] }] },
+            "layer": { "name": "VK_LAYER_test", "type": "GLOBAL" }
+        }"#;
+
+        let layer = find_member(manifest, "layer").expect("layer exists");
+        let name = find_member(layer, "name")
+            .and_then(parse_string)
+            .map(|(name, _)| name);
+
+        assert_eq!(name.as_deref(), Some("VK_LAYER_test"));
+        assert_eq!(find_member(manifest, "missing"), None);
+    }
+
+    #[test]
+    fn skips_values() {
+        assert_eq!(skip_value(r#""a,b", 1"#), Some(", 1"));
+        assert_eq!(skip_value("123, 1"), Some(", 1"));
+        assert_eq!(skip_value(r#"{"a": ["]", {}]}, 1"#), Some(", 1"));
+    }
+}
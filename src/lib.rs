@@ -2,26 +2,63 @@
 
 extern crate alloc;
 
+pub use align::*;
 pub use allocation::*;
+pub use bytes::*;
 pub use cleanup::*;
 pub use commands::*;
+pub use debug_fmt::*;
 pub use debug_utils::*;
+pub use deletion::*;
+pub use descriptor::*;
 pub use fence::*;
+pub use format::*;
+pub use image_view::*;
 pub use layer::*;
 pub(crate) use maybe_mutex::*;
+pub use physical_device::*;
+pub use pipeline::*;
+pub use pipeline_cache::*;
+pub use query::*;
+pub use queue::*;
 pub use result::*;
+pub use sampler::*;
+pub use semaphore::*;
 pub use shader::*;
+pub use subgroup::*;
 pub use swapchain::*;
+pub use sync::*;
+pub use texture::*;
 pub use vulkan_context::*;
 
+mod align;
 mod allocation;
+mod bytes;
 mod cleanup;
 mod commands;
+mod debug_fmt;
 mod debug_utils;
+mod deletion;
+mod descriptor;
 mod fence;
+mod format;
+mod image_view;
 mod layer;
+mod logging;
 mod maybe_mutex;
+mod physical_device;
+mod pipeline;
+mod pipeline_cache;
+mod query;
+mod queue;
+/// Helpers for querying and validating device requirements.
+pub mod requirements;
 mod result;
+mod sampler;
+mod semaphore;
 mod shader;
+mod subgroup;
 mod swapchain;
+mod sync;
+mod texture;
 mod vulkan_context;
@@ -0,0 +1,20 @@
+#[cfg(feature = "tracing")]
+pub(crate) use tracing::{debug, error, info, warn};
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) use no_op::{debug, error, info, warn};
+
+#[cfg(not(feature = "tracing"))]
+mod no_op {
+    /// Discards the log message while still type checking the arguments.
+    macro_rules! no_op {
+        ($($argument:tt)*) => {{
+            let _ = format_args!($($argument)*);
+        }};
+    }
+
+    pub(crate) use no_op as debug;
+    pub(crate) use no_op as error;
+    pub(crate) use no_op as info;
+    pub(crate) use no_op as warn;
+}
@@ -1,8 +1,38 @@
-use parking_lot::{Mutex, MutexGuard};
+#[cfg(feature = "parking_lot")]
+pub use parking_lot::{Mutex, MutexGuard};
+#[cfg(not(feature = "parking_lot"))]
+pub use std::sync::{Mutex, MutexGuard};
 
+/// Locks `mutex`, a poisoned `std::sync::Mutex` is still locked as the guarded Vulkan handles
+/// remain valid.
+pub fn lock_mutex<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    #[cfg(feature = "parking_lot")]
+    {
+        mutex.lock()
+    }
+
+    #[cfg(not(feature = "parking_lot"))]
+    {
+        mutex
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[derive(Clone, Copy)]
 pub enum MaybeMutex<'m, T: Copy> {
     Raw(T),
-    Mutex(&'m Mutex<T>),
+    Std(&'m std::sync::Mutex<T>),
+    #[cfg(feature = "parking_lot")]
+    ParkingLot(&'m parking_lot::Mutex<T>),
+}
+
+/// The guard of a locked [`MaybeMutex`], it is only held until dropped.
+#[allow(dead_code)]
+pub enum MaybeMutexGuard<'m, T> {
+    Std(std::sync::MutexGuard<'m, T>),
+    #[cfg(feature = "parking_lot")]
+    ParkingLot(parking_lot::MutexGuard<'m, T>),
 }
 
 impl<T: Copy> From<T> for MaybeMutex<'_, T> {
@@ -11,19 +41,33 @@ impl<T: Copy> From<T> for MaybeMutex<'_, T> {
     }
 }
 
-impl<'m, T: Copy> From<&'m Mutex<T>> for MaybeMutex<'m, T> {
-    fn from(value: &'m Mutex<T>) -> Self {
-        Self::Mutex(value)
+impl<'m, T: Copy> From<&'m std::sync::Mutex<T>> for MaybeMutex<'m, T> {
+    fn from(value: &'m std::sync::Mutex<T>) -> Self {
+        Self::Std(value)
+    }
+}
+
+#[cfg(feature = "parking_lot")]
+impl<'m, T: Copy> From<&'m parking_lot::Mutex<T>> for MaybeMutex<'m, T> {
+    fn from(value: &'m parking_lot::Mutex<T>) -> Self {
+        Self::ParkingLot(value)
     }
 }
 
 impl<'m, T: Copy> MaybeMutex<'m, T> {
-    pub fn lock(&self) -> (T, Option<MutexGuard<'m, T>>) {
+    pub fn lock(&self) -> (T, Option<MaybeMutexGuard<'m, T>>) {
         match self {
             MaybeMutex::Raw(value) => (*value, None),
-            MaybeMutex::Mutex(mutex) => {
+            MaybeMutex::Std(mutex) => {
+                let guard = mutex
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                (*guard, Some(MaybeMutexGuard::Std(guard)))
+            }
+            #[cfg(feature = "parking_lot")]
+            MaybeMutex::ParkingLot(mutex) => {
                 let guard = mutex.lock();
-                (*guard, Some(guard))
+                (*guard, Some(MaybeMutexGuard::ParkingLot(guard)))
             }
         }
     }
@@ -0,0 +1,193 @@
+use core::{ffi::CStr, ptr};
+
+use ash::{khr, vk};
+
+use crate::{LabelledVkResult, VkError, VulkanContext};
+
+/// Returns the device extensions provided by the implementation, or by `layer_name` if it is
+/// `Some`.
+pub unsafe fn device_extension_properties(
+    instance: &ash::Instance,
+    physical_device: vk::PhysicalDevice,
+    layer_name: Option<&CStr>,
+) -> LabelledVkResult<Vec<vk::ExtensionProperties>> {
+    let p_layer_name = layer_name.map_or(ptr::null(), CStr::as_ptr);
+    let enumerate = instance.fp_v1_0().enumerate_device_extension_properties;
+
+    // The count may change between calls, so retry while the result is incomplete.
+    loop {
+        let mut count = 0;
+        unsafe { enumerate(physical_device, p_layer_name, &mut count, ptr::null_mut()) }
+            .result()
+            .map_err(|e| VkError::new(e, "vkEnumerateDeviceExtensionProperties"))?;
+
+        let mut properties = vec![vk::ExtensionProperties::default(); count as usize];
+        let result = unsafe {
+            enumerate(
+                physical_device,
+                p_layer_name,
+                &mut count,
+                properties.as_mut_ptr(),
+            )
+        };
+
+        match result {
+            vk::Result::INCOMPLETE => continue,
+
+            result => {
+                result
+                    .result()
+                    .map_err(|e| VkError::new(e, "vkEnumerateDeviceExtensionProperties"))?;
+
+                properties.truncate(count as usize);
+                return Ok(properties);
+            }
+        }
+    }
+}
+
+/// Returns the indices of the queue families on `physical_device` that can present to `surface`.
+pub unsafe fn present_families(
+    instance: &ash::Instance,
+    surface_instance: &khr::surface::Instance,
+    physical_device: vk::PhysicalDevice,
+    surface: vk::SurfaceKHR,
+) -> LabelledVkResult<Vec<u32>> {
+    let family_count =
+        unsafe { instance.get_physical_device_queue_family_properties(physical_device) }.len();
+
+    let mut families = Vec::with_capacity(family_count);
+    for family_index in 0..family_count as u32 {
+        let supports_present = unsafe {
+            surface_instance.get_physical_device_surface_support(
+                physical_device,
+                family_index,
+                surface,
+            )
+        }
+        .map_err(|e| VkError::new(e, "vkGetPhysicalDeviceSurfaceSupportKHR"))?;
+
+        if supports_present {
+            families.push(family_index);
+        }
+    }
+
+    Ok(families)
+}
+
+/// Returns a queue family on `physical_device` that supports both graphics and presenting to
+/// `surface`, or `None` if graphics and present must use separate queue families.
+pub unsafe fn unified_graphics_present_family(
+    instance: &ash::Instance,
+    surface_instance: &khr::surface::Instance,
+    physical_device: vk::PhysicalDevice,
+    surface: vk::SurfaceKHR,
+) -> LabelledVkResult<Option<u32>> {
+    let families = unsafe { instance.get_physical_device_queue_family_properties(physical_device) };
+
+    let present_families =
+        unsafe { present_families(instance, surface_instance, physical_device, surface) }?;
+
+    let unified_family = present_families.into_iter().find(|&family_index| {
+        families[family_index as usize]
+            .queue_flags
+            .contains(vk::QueueFlags::GRAPHICS)
+    });
+
+    Ok(unified_family)
+}
+
+/// Returns the number of meaningful bits in timestamps written on queues from the queue family, zero
+/// if the queue family does not support timestamps. Returns `None` if the queue family does not
+/// exist.
+pub fn queue_timestamp_valid_bits<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    family_index: u32,
+) -> Option<u32> {
+    let families = unsafe {
+        vulkan
+            .instance()
+            .get_physical_device_queue_family_properties(vulkan.physical_device())
+    };
+
+    families
+        .get(family_index as usize)
+        .map(|family| family.timestamp_valid_bits)
+}
+
+/// Returns the `minImageTransferGranularity` of the queue family, the alignment image transfer
+/// offsets and extents must respect on queues from the family. Returns `None` if the queue family
+/// does not exist.
+///
+/// The copy helpers always copy whole subresources, which respect any granularity.
+pub fn transfer_granularity<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    family_index: u32,
+) -> Option<vk::Extent3D> {
+    let families = unsafe {
+        vulkan
+            .instance()
+            .get_physical_device_queue_family_properties(vulkan.physical_device())
+    };
+
+    families
+        .get(family_index as usize)
+        .map(|family| family.min_image_transfer_granularity)
+}
+
+/// Returns the sparse image properties for images created with the parameters, empty if sparse
+/// images are not supported with the parameters.
+pub fn sparse_image_format_properties<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    format: vk::Format,
+    image_type: vk::ImageType,
+    samples: vk::SampleCountFlags,
+    usage: vk::ImageUsageFlags,
+    tiling: vk::ImageTiling,
+) -> Vec<vk::SparseImageFormatProperties> {
+    unsafe {
+        vulkan
+            .instance()
+            .get_physical_device_sparse_image_format_properties(
+                vulkan.physical_device(),
+                format,
+                image_type,
+                samples,
+                usage,
+                tiling,
+            )
+    }
+}
+
+/// A group of physical devices that can be used to create a single logical device.
+#[derive(Debug, Clone)]
+pub struct PhysicalDeviceGroup {
+    /// The physical devices in the group.
+    pub physical_devices: Vec<vk::PhysicalDevice>,
+    /// If memory allocations can be made on a subset of the devices in the group.
+    pub subset_allocation: bool,
+}
+
+impl PhysicalDeviceGroup {
+    /// Enumerates the physical device groups, requires Vulkan 1.1.
+    pub unsafe fn get_all(instance: &ash::Instance) -> LabelledVkResult<Vec<Self>> {
+        let group_count = unsafe { instance.enumerate_physical_device_groups_len() }
+            .map_err(|e| VkError::new(e, "vkEnumeratePhysicalDeviceGroups"))?;
+
+        let mut properties = vec![vk::PhysicalDeviceGroupProperties::default(); group_count];
+        unsafe { instance.enumerate_physical_device_groups(&mut properties) }
+            .map_err(|e| VkError::new(e, "vkEnumeratePhysicalDeviceGroups"))?;
+
+        let groups = properties
+            .iter()
+            .map(|properties| Self {
+                physical_devices: properties.physical_devices
+                    [..properties.physical_device_count as usize]
+                    .to_vec(),
+                subset_allocation: properties.subset_allocation == vk::TRUE,
+            })
+            .collect();
+
+        Ok(groups)
+    }
+}
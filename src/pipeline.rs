@@ -0,0 +1,626 @@
+use core::{ffi::CStr, marker::PhantomData, slice};
+
+use ash::vk;
+
+use crate::{
+    LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, cmd_push_constants,
+    create_shader_module_from_spv, format_aspect_mask, try_name,
+};
+
+/// Describes a compute pipeline for [`create_compute_pipelines`].
+#[derive(Clone, Copy)]
+pub struct ComputePipelineDesc<'a> {
+    /// The compute shader's SPV bytes.
+    pub spv: &'a [u8],
+    /// The compute shader's entry point.
+    pub entry_point: &'a CStr,
+    /// The pipeline's layout.
+    pub layout: vk::PipelineLayout,
+    /// The name used to label the pipeline and shader module.
+    pub name: &'a str,
+}
+
+/// Creates a compute pipeline for each description in a single `vkCreateComputePipelines` call,
+/// cleaning up any created objects on failure.
+///
+/// Each pipeline is named `{name} Pipeline` and each shader module `{name} Shader Module`.
+///
+/// # Panics
+/// - If the `read_spv` call fails on any `spv`.
+///
+/// # Safety
+/// - Each `spv` **must** be valid SPV according to <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkShaderModuleCreateInfo.html>.
+pub unsafe fn create_compute_pipelines<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    descriptions: &[ComputePipelineDesc<'_>],
+    cache: vk::PipelineCache,
+) -> LabelledVkResult<Vec<(vk::Pipeline, vk::ShaderModule)>> {
+    let destroy_shader_modules = |shader_modules: &[vk::ShaderModule]| {
+        shader_modules.iter().for_each(|shader_module| unsafe {
+            vulkan
+                .device()
+                .destroy_shader_module(*shader_module, VK_GLOBAL_ALLOCATOR.as_deref())
+        });
+    };
+
+    // Create the shader modules
+    let shader_modules = {
+        let mut shader_modules = Vec::with_capacity(descriptions.len());
+
+        for description in descriptions {
+            match unsafe { create_shader_module_from_spv(vulkan, description.spv) } {
+                Ok(shader_module) => shader_modules.push(shader_module),
+
+                Err(error) => {
+                    destroy_shader_modules(&shader_modules);
+                    return Err(error);
+                }
+            }
+        }
+
+        shader_modules
+    };
+
+    // Create the pipelines
+    let pipelines = {
+        let create_infos: Vec<_> = descriptions
+            .iter()
+            .zip(&shader_modules)
+            .map(|(description, &shader_module)| {
+                let stage = vk::PipelineShaderStageCreateInfo::default()
+                    .stage(vk::ShaderStageFlags::COMPUTE)
+                    .module(shader_module)
+                    .name(description.entry_point);
+
+                vk::ComputePipelineCreateInfo::default()
+                    .stage(stage)
+                    .layout(description.layout)
+            })
+            .collect();
+
+        let result = unsafe {
+            vulkan.device().create_compute_pipelines(
+                cache,
+                &create_infos,
+                VK_GLOBAL_ALLOCATOR.as_deref(),
+            )
+        };
+
+        match result {
+            Ok(pipelines) => pipelines,
+
+            Err((pipelines, error)) => {
+                // Cleanup any created pipelines
+                pipelines
+                    .into_iter()
+                    .filter(|pipeline| *pipeline != vk::Pipeline::null())
+                    .for_each(|pipeline| unsafe {
+                        vulkan
+                            .device()
+                            .destroy_pipeline(pipeline, VK_GLOBAL_ALLOCATOR.as_deref())
+                    });
+                destroy_shader_modules(&shader_modules);
+
+                return Err(VkError::new(error, "vkCreateComputePipelines"));
+            }
+        }
+    };
+
+    // Name the objects
+    for ((description, &pipeline), &shader_module) in
+        descriptions.iter().zip(&pipelines).zip(&shader_modules)
+    {
+        let name = description.name;
+
+        unsafe {
+            try_name(vulkan, pipeline, &format!("{name} Pipeline"));
+            try_name(vulkan, shader_module, &format!("{name} Shader Module"));
+        }
+    }
+
+    Ok(pipelines.into_iter().zip(shader_modules).collect())
+}
+
+/// Builds a [`ComputePipeline`] with a push constant block of type `PushConstants`, use `()` for
+/// no push constants.
+#[derive(Clone, Copy)]
+pub struct ComputePipelineBuilder<'a, PushConstants: Copy = ()> {
+    /// The compute shader's SPV bytes.
+    pub spv: &'a [u8],
+    /// The compute shader's entry point, defaults to `main`.
+    pub entry_point: &'a CStr,
+    /// The descriptor set layouts of the pipeline layout.
+    pub set_layouts: &'a [vk::DescriptorSetLayout],
+    /// The pipeline cache, defaults to no cache.
+    pub cache: vk::PipelineCache,
+    /// The name used to label the pipeline, layout, and shader module.
+    pub name: &'a str,
+    phantom: PhantomData<PushConstants>,
+}
+
+impl<'a, PushConstants: Copy> ComputePipelineBuilder<'a, PushConstants> {
+    /// Create a new builder for a compute shader's SPV bytes.
+    pub fn new(spv: &'a [u8], name: &'a str) -> Self {
+        Self {
+            spv,
+            entry_point: c"main",
+            set_layouts: &[],
+            cache: vk::PipelineCache::null(),
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Set the entry point.
+    pub fn entry_point(mut self, entry_point: &'a CStr) -> Self {
+        self.entry_point = entry_point;
+        self
+    }
+
+    /// Set the descriptor set layouts.
+    pub fn set_layouts(mut self, set_layouts: &'a [vk::DescriptorSetLayout]) -> Self {
+        self.set_layouts = set_layouts;
+        self
+    }
+
+    /// Set the pipeline cache.
+    pub fn cache(mut self, cache: vk::PipelineCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Creates the pipeline layout and pipeline, cleaning up any created objects on failure.
+    ///
+    /// # Panics
+    /// - If the `read_spv` call fails on `spv`.
+    ///
+    /// # Safety
+    /// - `spv` **must** be valid SPV according to <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkShaderModuleCreateInfo.html>.
+    /// - `PushConstants` **must** match the layout of the shader's push constant block.
+    pub unsafe fn build<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+    ) -> LabelledVkResult<ComputePipeline<PushConstants>> {
+        let push_constant_range = vk::PushConstantRange::default()
+            .stage_flags(vk::ShaderStageFlags::COMPUTE)
+            .offset(0)
+            .size(size_of::<PushConstants>() as u32);
+
+        let push_constant_ranges: &[_] = if size_of::<PushConstants>() == 0 {
+            &[]
+        } else {
+            slice::from_ref(&push_constant_range)
+        };
+
+        // Create the layout
+        let layout = {
+            let create_info = vk::PipelineLayoutCreateInfo::default()
+                .set_layouts(self.set_layouts)
+                .push_constant_ranges(push_constant_ranges);
+
+            let layout = unsafe {
+                vulkan
+                    .device()
+                    .create_pipeline_layout(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+            }
+            .map_err(|e| VkError::new(e, "vkCreatePipelineLayout"))?;
+
+            unsafe { try_name(vulkan, layout, &format!("{} Pipeline Layout", self.name)) };
+
+            layout
+        };
+
+        // Create the pipeline
+        let description = ComputePipelineDesc {
+            spv: self.spv,
+            entry_point: self.entry_point,
+            layout,
+            name: self.name,
+        };
+
+        let (pipeline, shader_module) = match unsafe {
+            create_compute_pipelines(vulkan, slice::from_ref(&description), self.cache)
+        } {
+            Ok(pipelines) => pipelines[0],
+
+            Err(error) => {
+                unsafe {
+                    vulkan
+                        .device()
+                        .destroy_pipeline_layout(layout, VK_GLOBAL_ALLOCATOR.as_deref())
+                };
+
+                return Err(error);
+            }
+        };
+
+        let limits = unsafe {
+            vulkan
+                .instance()
+                .get_physical_device_properties(vulkan.physical_device())
+        }
+        .limits;
+
+        Ok(ComputePipeline {
+            pipeline,
+            layout,
+            shader_module,
+            push_constant_range: push_constant_ranges.first().copied(),
+            max_workgroup_count: limits.max_compute_work_group_count,
+            max_push_constants_size: limits.max_push_constants_size,
+            phantom: PhantomData,
+        })
+    }
+}
+
+/// A compute pipeline with its layout and shader module, created by [`ComputePipelineBuilder`].
+pub struct ComputePipeline<PushConstants: Copy = ()> {
+    /// The pipeline.
+    pub pipeline: vk::Pipeline,
+    /// The pipeline's layout.
+    pub layout: vk::PipelineLayout,
+    /// The pipeline's shader module.
+    pub shader_module: vk::ShaderModule,
+    /// The layout's push constant range, `None` if `PushConstants` is zero sized.
+    pub push_constant_range: Option<vk::PushConstantRange>,
+    /// The device's `maxComputeWorkGroupCount`.
+    pub max_workgroup_count: [u32; 3],
+    /// The device's `maxPushConstantsSize`.
+    pub max_push_constants_size: u32,
+    phantom: PhantomData<PushConstants>,
+}
+
+impl<PushConstants: Copy> ComputePipeline<PushConstants> {
+    /// Records binding the pipeline and `descriptor_sets` starting at set `0`.
+    pub unsafe fn cmd_bind<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+        descriptor_sets: &[vk::DescriptorSet],
+    ) {
+        unsafe {
+            vulkan.device().cmd_bind_pipeline(
+                command_buffer,
+                vk::PipelineBindPoint::COMPUTE,
+                self.pipeline,
+            );
+
+            if !descriptor_sets.is_empty() {
+                vulkan.device().cmd_bind_descriptor_sets(
+                    command_buffer,
+                    vk::PipelineBindPoint::COMPUTE,
+                    self.layout,
+                    0,
+                    descriptor_sets,
+                    &[],
+                );
+            }
+        }
+    }
+
+    /// Records pushing `push_constants`.
+    ///
+    /// # Safety
+    /// * `PushConstants` **MUST NOT** contain padding bytes, as reading padding is undefined
+    ///   behaviour.
+    pub unsafe fn cmd_push_constants<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+        push_constants: &PushConstants,
+    ) {
+        unsafe {
+            cmd_push_constants(
+                vulkan,
+                command_buffer,
+                self.layout,
+                self.push_constant_range.as_slice(),
+                self.max_push_constants_size,
+                vk::ShaderStageFlags::COMPUTE,
+                0,
+                push_constants,
+            )
+        };
+    }
+
+    /// Records dispatching `workgroup_count` workgroups.
+    ///
+    /// # Panics
+    /// * If any dimension of `workgroup_count` exceeds `maxComputeWorkGroupCount`.
+    pub unsafe fn cmd_dispatch<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+        workgroup_count: [u32; 3],
+    ) {
+        assert!(
+            workgroup_count
+                .iter()
+                .zip(self.max_workgroup_count)
+                .all(|(&count, max_count)| count <= max_count),
+            "Workgroup count {workgroup_count:?} exceeds maxComputeWorkGroupCount {:?}",
+            self.max_workgroup_count
+        );
+
+        let [x, y, z] = workgroup_count;
+        unsafe { vulkan.device().cmd_dispatch(command_buffer, x, y, z) };
+    }
+
+    /// Destroys the pipeline, layout, and shader module.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
+        unsafe {
+            vulkan
+                .device()
+                .destroy_pipeline(self.pipeline, VK_GLOBAL_ALLOCATOR.as_deref());
+            vulkan
+                .device()
+                .destroy_pipeline_layout(self.layout, VK_GLOBAL_ALLOCATOR.as_deref());
+            vulkan
+                .device()
+                .destroy_shader_module(self.shader_module, VK_GLOBAL_ALLOCATOR.as_deref());
+        }
+    }
+}
+
+/// Builds a graphics pipeline for dynamic rendering from vertex and fragment SPV bytes.
+///
+/// Viewport and scissor are dynamic state. Defaults to a triangle list, no culling,
+/// counter-clockwise front faces, filled polygons, no blending, and no depth attachment.
+#[derive(Clone, Copy)]
+pub struct GraphicsPipelineBuilder<'a> {
+    /// The vertex shader's SPV bytes.
+    pub vertex_spv: &'a [u8],
+    /// The fragment shader's SPV bytes.
+    pub fragment_spv: &'a [u8],
+    /// The entry point of both shaders, defaults to `main`.
+    pub entry_point: &'a CStr,
+    /// The pipeline's layout.
+    pub layout: vk::PipelineLayout,
+    /// The formats of the colour attachments.
+    pub colour_formats: &'a [vk::Format],
+    /// The format of the depth attachment, `UNDEFINED` for none.
+    pub depth_format: vk::Format,
+    /// The depth compare operation, used if there is a depth attachment.
+    pub depth_compare_op: vk::CompareOp,
+    /// The vertex input bindings.
+    pub vertex_bindings: &'a [vk::VertexInputBindingDescription],
+    /// The vertex input attributes.
+    pub vertex_attributes: &'a [vk::VertexInputAttributeDescription],
+    /// The primitive topology.
+    pub topology: vk::PrimitiveTopology,
+    /// The polygon mode.
+    pub polygon_mode: vk::PolygonMode,
+    /// The cull mode.
+    pub cull_mode: vk::CullModeFlags,
+    /// The front face.
+    pub front_face: vk::FrontFace,
+    /// If the colour attachments use `SRC_ALPHA`, `ONE_MINUS_SRC_ALPHA` blending.
+    pub alpha_blend: bool,
+    /// The pipeline cache, defaults to no cache.
+    pub cache: vk::PipelineCache,
+    /// The name used to label the pipeline.
+    pub name: &'a str,
+}
+
+impl<'a> GraphicsPipelineBuilder<'a> {
+    /// Create a new builder rendering to `colour_formats` with the defaults.
+    pub fn new(
+        vertex_spv: &'a [u8],
+        fragment_spv: &'a [u8],
+        layout: vk::PipelineLayout,
+        colour_formats: &'a [vk::Format],
+        name: &'a str,
+    ) -> Self {
+        Self {
+            vertex_spv,
+            fragment_spv,
+            entry_point: c"main",
+            layout,
+            colour_formats,
+            depth_format: vk::Format::UNDEFINED,
+            depth_compare_op: vk::CompareOp::LESS,
+            vertex_bindings: &[],
+            vertex_attributes: &[],
+            topology: vk::PrimitiveTopology::TRIANGLE_LIST,
+            polygon_mode: vk::PolygonMode::FILL,
+            cull_mode: vk::CullModeFlags::NONE,
+            front_face: vk::FrontFace::COUNTER_CLOCKWISE,
+            alpha_blend: false,
+            cache: vk::PipelineCache::null(),
+            name,
+        }
+    }
+
+    /// Set the entry point of both shaders.
+    pub fn entry_point(mut self, entry_point: &'a CStr) -> Self {
+        self.entry_point = entry_point;
+        self
+    }
+
+    /// Set the depth attachment format and compare operation, enabling depth testing and writes.
+    /// A combined depth/stencil format is also set as the stencil attachment format.
+    pub fn depth(mut self, depth_format: vk::Format, depth_compare_op: vk::CompareOp) -> Self {
+        self.depth_format = depth_format;
+        self.depth_compare_op = depth_compare_op;
+        self
+    }
+
+    /// Set the vertex input bindings and attributes.
+    pub fn vertex_input(
+        mut self,
+        vertex_bindings: &'a [vk::VertexInputBindingDescription],
+        vertex_attributes: &'a [vk::VertexInputAttributeDescription],
+    ) -> Self {
+        self.vertex_bindings = vertex_bindings;
+        self.vertex_attributes = vertex_attributes;
+        self
+    }
+
+    /// Set the primitive topology.
+    pub fn topology(mut self, topology: vk::PrimitiveTopology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Set the polygon mode.
+    pub fn polygon_mode(mut self, polygon_mode: vk::PolygonMode) -> Self {
+        self.polygon_mode = polygon_mode;
+        self
+    }
+
+    /// Set the cull mode and front face.
+    pub fn cull(mut self, cull_mode: vk::CullModeFlags, front_face: vk::FrontFace) -> Self {
+        self.cull_mode = cull_mode;
+        self.front_face = front_face;
+        self
+    }
+
+    /// Set if the colour attachments use alpha blending.
+    pub fn alpha_blend(mut self, alpha_blend: bool) -> Self {
+        self.alpha_blend = alpha_blend;
+        self
+    }
+
+    /// Set the pipeline cache.
+    pub fn cache(mut self, cache: vk::PipelineCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Creates the pipeline, the shader modules are destroyed once the pipeline is created.
+    ///
+    /// # Panics
+    /// - If the `read_spv` call fails on either shader.
+    ///
+    /// # Safety
+    /// - Both shaders **must** be valid SPV according to <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkShaderModuleCreateInfo.html>.
+    /// - `dynamicRendering` **must** be enabled.
+    pub unsafe fn build<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+    ) -> LabelledVkResult<vk::Pipeline> {
+        let destroy_shader_modules = |shader_modules: &[vk::ShaderModule]| {
+            shader_modules.iter().for_each(|shader_module| unsafe {
+                vulkan
+                    .device()
+                    .destroy_shader_module(*shader_module, VK_GLOBAL_ALLOCATOR.as_deref())
+            });
+        };
+
+        // Create the shader modules
+        let vertex_module = unsafe { create_shader_module_from_spv(vulkan, self.vertex_spv) }?;
+        let fragment_module =
+            match unsafe { create_shader_module_from_spv(vulkan, self.fragment_spv) } {
+                Ok(fragment_module) => fragment_module,
+                Err(error) => {
+                    destroy_shader_modules(&[vertex_module]);
+                    return Err(error);
+                }
+            };
+
+        // Create the pipeline
+        let result = {
+            let stages = [
+                vk::PipelineShaderStageCreateInfo::default()
+                    .stage(vk::ShaderStageFlags::VERTEX)
+                    .module(vertex_module)
+                    .name(self.entry_point),
+                vk::PipelineShaderStageCreateInfo::default()
+                    .stage(vk::ShaderStageFlags::FRAGMENT)
+                    .module(fragment_module)
+                    .name(self.entry_point),
+            ];
+
+            let vertex_input = vk::PipelineVertexInputStateCreateInfo::default()
+                .vertex_binding_descriptions(self.vertex_bindings)
+                .vertex_attribute_descriptions(self.vertex_attributes);
+
+            let input_assembly =
+                vk::PipelineInputAssemblyStateCreateInfo::default().topology(self.topology);
+
+            let viewport = vk::PipelineViewportStateCreateInfo::default()
+                .viewport_count(1)
+                .scissor_count(1);
+
+            let rasterization = vk::PipelineRasterizationStateCreateInfo::default()
+                .polygon_mode(self.polygon_mode)
+                .cull_mode(self.cull_mode)
+                .front_face(self.front_face)
+                .line_width(1.0);
+
+            let multisample = vk::PipelineMultisampleStateCreateInfo::default()
+                .rasterization_samples(vk::SampleCountFlags::TYPE_1);
+
+            let has_depth = self.depth_format != vk::Format::UNDEFINED;
+            let depth_stencil = vk::PipelineDepthStencilStateCreateInfo::default()
+                .depth_test_enable(has_depth)
+                .depth_write_enable(has_depth)
+                .depth_compare_op(self.depth_compare_op);
+
+            let blend_attachment = if self.alpha_blend {
+                vk::PipelineColorBlendAttachmentState::default()
+                    .blend_enable(true)
+                    .src_color_blend_factor(vk::BlendFactor::SRC_ALPHA)
+                    .dst_color_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+                    .color_blend_op(vk::BlendOp::ADD)
+                    .src_alpha_blend_factor(vk::BlendFactor::ONE)
+                    .dst_alpha_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+                    .alpha_blend_op(vk::BlendOp::ADD)
+                    .color_write_mask(vk::ColorComponentFlags::RGBA)
+            } else {
+                vk::PipelineColorBlendAttachmentState::default()
+                    .color_write_mask(vk::ColorComponentFlags::RGBA)
+            };
+            let blend_attachments = vec![blend_attachment; self.colour_formats.len()];
+            let colour_blend =
+                vk::PipelineColorBlendStateCreateInfo::default().attachments(&blend_attachments);
+
+            let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+            let dynamic_state =
+                vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
+
+            // A combined depth/stencil format is also the stencil attachment's format.
+            let stencil_format =
+                if format_aspect_mask(self.depth_format).contains(vk::ImageAspectFlags::STENCIL) {
+                    self.depth_format
+                } else {
+                    vk::Format::UNDEFINED
+                };
+
+            let mut rendering_info = vk::PipelineRenderingCreateInfo::default()
+                .color_attachment_formats(self.colour_formats)
+                .depth_attachment_format(self.depth_format)
+                .stencil_attachment_format(stencil_format);
+
+            let create_info = vk::GraphicsPipelineCreateInfo::default()
+                .stages(&stages)
+                .vertex_input_state(&vertex_input)
+                .input_assembly_state(&input_assembly)
+                .viewport_state(&viewport)
+                .rasterization_state(&rasterization)
+                .multisample_state(&multisample)
+                .depth_stencil_state(&depth_stencil)
+                .color_blend_state(&colour_blend)
+                .dynamic_state(&dynamic_state)
+                .layout(self.layout)
+                .push_next(&mut rendering_info);
+
+            unsafe {
+                vulkan.device().create_graphics_pipelines(
+                    self.cache,
+                    slice::from_ref(&create_info),
+                    VK_GLOBAL_ALLOCATOR.as_deref(),
+                )
+            }
+        };
+
+        // The shader modules are no longer needed
+        destroy_shader_modules(&[vertex_module, fragment_module]);
+
+        let pipeline = result.map_err(|(_, e)| VkError::new(e, "vkCreateGraphicsPipelines"))?[0];
+
+        unsafe { try_name(vulkan, pipeline, &format!("{} Pipeline", self.name)) };
+
+        Ok(pipeline)
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use ash::vk;
+use thiserror::Error;
+
+use crate::{
+    LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, logging::warn, try_name,
+};
+
+/// The size of a `VK_PIPELINE_CACHE_HEADER_VERSION_ONE` header.
+const HEADER_SIZE: usize = 16 + vk::UUID_SIZE;
+
+/// Pipeline cache saving failure reason.
+#[derive(Debug, Error)]
+pub enum PipelineCacheError {
+    /// Getting the cache data failed at a Vulkan call.
+    #[error(transparent)]
+    VkError(#[from] VkError),
+
+    /// Writing the cache data to disk failed.
+    #[error("Failed to write pipeline cache:\n{0}")]
+    Io(#[from] io::Error),
+}
+
+/// Returns if `data` starts with a pipeline cache header that matches this device's vendor,
+/// device, and pipeline cache UUID, so it can be used as initial data.
+pub fn pipeline_cache_header_is_valid<Vulkan: VulkanContext>(vulkan: &Vulkan, data: &[u8]) -> bool {
+    if data.len() < HEADER_SIZE {
+        return false;
+    }
+
+    let read_u32 = |offset: usize| {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(&data[offset..offset + 4]);
+        u32::from_le_bytes(bytes)
+    };
+
+    let header_size = read_u32(0);
+    let header_version = read_u32(4);
+    let vendor_id = read_u32(8);
+    let device_id = read_u32(12);
+    let cache_uuid = &data[16..HEADER_SIZE];
+
+    let properties = unsafe {
+        vulkan
+            .instance()
+            .get_physical_device_properties(vulkan.physical_device())
+    };
+
+    header_size as usize >= HEADER_SIZE
+        && vk::PipelineCacheHeaderVersion::from_raw(header_version as i32)
+            == vk::PipelineCacheHeaderVersion::ONE
+        && vendor_id == properties.vendor_id
+        && device_id == properties.device_id
+        && cache_uuid == properties.pipeline_cache_uuid
+}
+
+/// Creates a pipeline cache, using the contents of the file at `path` as initial data if it
+/// exists and its header matches this device. An unreadable or mismatched file results in an
+/// empty cache.
+pub unsafe fn create_pipeline_cache<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    path: &Path,
+    label: &str,
+) -> LabelledVkResult<vk::PipelineCache> {
+    let initial_data = match fs::read(path) {
+        Ok(data) if pipeline_cache_header_is_valid(vulkan, &data) => data,
+
+        Ok(_) => {
+            warn!(
+                "Pipeline cache {} does not match this device, ignoring it",
+                path.display()
+            );
+            vec![]
+        }
+
+        Err(e) if e.kind() == io::ErrorKind::NotFound => vec![],
+
+        Err(e) => {
+            warn!("Failed to read pipeline cache {}: {e}", path.display());
+            vec![]
+        }
+    };
+
+    let create_info = vk::PipelineCacheCreateInfo::default().initial_data(&initial_data);
+
+    let cache = unsafe {
+        vulkan
+            .device()
+            .create_pipeline_cache(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+    }
+    .map_err(|e| VkError::new(e, "vkCreatePipelineCache"))?;
+
+    unsafe { try_name(vulkan, cache, &format!("{label} Pipeline Cache")) };
+
+    Ok(cache)
+}
+
+/// Saves the data of `cache` to `path`. The data is written and synced to a temporary file beside
+/// `path`, named `path` with `.tmp` appended, which is then renamed over `path`, so an interrupted
+/// save never leaves a partial cache.
+pub unsafe fn save_pipeline_cache<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    cache: vk::PipelineCache,
+    path: &Path,
+) -> Result<(), PipelineCacheError> {
+    let data = unsafe { vulkan.device().get_pipeline_cache_data(cache) }
+        .map_err(|e| VkError::new(e, "vkGetPipelineCacheData"))?;
+
+    let temporary_path = {
+        let mut temporary_path = path.as_os_str().to_owned();
+        temporary_path.push(".tmp");
+        PathBuf::from(temporary_path)
+    };
+
+    // Write the data and ensure it is on disk before it replaces the cache
+    {
+        let mut file = fs::File::create(&temporary_path)?;
+        file.write_all(&data)?;
+        file.sync_all()?;
+    }
+
+    fs::rename(&temporary_path, path)?;
+
+    Ok(())
+}
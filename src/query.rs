@@ -0,0 +1,377 @@
+use core::time::Duration;
+
+use ash::{ext, vk};
+use thiserror::Error;
+
+use crate::{
+    LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, queue_timestamp_valid_bits,
+    try_name,
+};
+
+/// Timestamp profiler creation failure reason.
+#[derive(Debug, Error)]
+pub enum ProfilerError {
+    /// Creation failed at a Vulkan call.
+    #[error(transparent)]
+    VkError(#[from] VkError),
+
+    /// The queue family reports zero timestamp valid bits, so timestamps written on it would be
+    /// meaningless, or the queue family does not exist.
+    #[error("Queue family {queue_family_index} does not support timestamps")]
+    TimestampsUnsupported {
+        /// The queue family that does not support timestamps.
+        queue_family_index: u32,
+    },
+}
+
+/// Records GPU timestamps into a query pool to measure the duration of GPU work.
+pub struct TimestampProfiler {
+    /// The timestamp query pool.
+    pub query_pool: vk::QueryPool,
+    /// The number of timestamps the query pool can hold.
+    pub capacity: u32,
+    /// The index of the next timestamp to write.
+    pub next_mark: u32,
+    /// The number of nanoseconds per timestamp tick.
+    pub timestamp_period: f32,
+    /// The number of meaningful bits in the timestamps.
+    pub timestamp_valid_bits: u32,
+}
+
+impl TimestampProfiler {
+    /// Creates a new profiler that can hold `capacity` timestamps between resets, for command
+    /// buffers submitted to queues from `queue_family_index`.
+    pub unsafe fn new<Vulkan: VulkanContext>(
+        vulkan: &Vulkan,
+        queue_family_index: u32,
+        capacity: u32,
+        label: &str,
+    ) -> Result<Self, ProfilerError> {
+        let Some(timestamp_valid_bits) =
+            queue_timestamp_valid_bits(vulkan, queue_family_index).filter(|&bits| bits != 0)
+        else {
+            return Err(ProfilerError::TimestampsUnsupported { queue_family_index });
+        };
+
+        let timestamp_period = unsafe {
+            vulkan
+                .instance()
+                .get_physical_device_properties(vulkan.physical_device())
+        }
+        .limits
+        .timestamp_period;
+
+        let query_pool = {
+            let create_info = vk::QueryPoolCreateInfo::default()
+                .query_type(vk::QueryType::TIMESTAMP)
+                .query_count(capacity);
+
+            let query_pool = unsafe {
+                vulkan
+                    .device()
+                    .create_query_pool(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+            }
+            .map_err(|e| VkError::new(e, "vkCreateQueryPool"))?;
+
+            unsafe { try_name(vulkan, query_pool, &format!("{label} Timestamp Query Pool")) };
+
+            query_pool
+        };
+
+        Ok(Self {
+            query_pool,
+            capacity,
+            next_mark: 0,
+            timestamp_period,
+            timestamp_valid_bits,
+        })
+    }
+
+    /// Records resetting all timestamps, this **MUST** be recorded before any timestamps are
+    /// written.
+    pub unsafe fn cmd_reset<Vulkan: VulkanContext>(
+        &mut self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+    ) {
+        unsafe {
+            vulkan
+                .device()
+                .cmd_reset_query_pool(command_buffer, self.query_pool, 0, self.capacity)
+        };
+
+        self.next_mark = 0;
+    }
+
+    /// Records writing a timestamp once all prior commands have completed `stage`, returning the
+    /// index of the mark. Returns `None` if the profiler is full.
+    pub unsafe fn cmd_write_timestamp<Vulkan: VulkanContext>(
+        &mut self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+        stage: vk::PipelineStageFlags,
+    ) -> Option<u32> {
+        if self.next_mark >= self.capacity {
+            return None;
+        }
+
+        let mark = self.next_mark;
+
+        unsafe {
+            vulkan
+                .device()
+                .cmd_write_timestamp(command_buffer, stage, self.query_pool, mark)
+        };
+
+        self.next_mark += 1;
+
+        Some(mark)
+    }
+
+    /// Waits for and reads the written timestamps, indexed by mark.
+    pub unsafe fn read_timestamps<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+    ) -> LabelledVkResult<Vec<u64>> {
+        let mut timestamps = vec![0u64; self.next_mark as usize];
+
+        if timestamps.is_empty() {
+            return Ok(timestamps);
+        }
+
+        unsafe {
+            vulkan.device().get_query_pool_results(
+                self.query_pool,
+                0,
+                &mut timestamps,
+                vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WAIT,
+            )
+        }
+        .map_err(|e| VkError::new(e, "vkGetQueryPoolResults"))?;
+
+        Ok(timestamps)
+    }
+
+    /// Returns the duration between the `start` and `end` marks of the read `timestamps`.
+    pub fn elapsed(&self, timestamps: &[u64], start: u32, end: u32) -> Duration {
+        let mask = if self.timestamp_valid_bits >= 64 {
+            u64::MAX
+        } else {
+            (1 << self.timestamp_valid_bits) - 1
+        };
+
+        let ticks = (timestamps[end as usize] & mask)
+            .wrapping_sub(timestamps[start as usize] & mask)
+            & mask;
+        let nanoseconds = ticks as f64 * f64::from(self.timestamp_period);
+
+        Duration::from_nanos(nanoseconds as u64)
+    }
+
+    /// Destroys the query pool.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
+        unsafe {
+            vulkan
+                .device()
+                .destroy_query_pool(self.query_pool, VK_GLOBAL_ALLOCATOR.as_deref())
+        };
+    }
+}
+
+/// Records occlusion queries into a query pool, counting the samples that pass the depth and
+/// stencil tests between a begin and end.
+pub struct OcclusionQuery {
+    /// The occlusion query pool.
+    pub query_pool: vk::QueryPool,
+    /// The number of queries the query pool can hold.
+    pub capacity: u32,
+}
+
+impl OcclusionQuery {
+    /// Creates a new occlusion query pool that can hold `capacity` queries.
+    pub unsafe fn new<Vulkan: VulkanContext>(
+        vulkan: &Vulkan,
+        capacity: u32,
+        label: &str,
+    ) -> LabelledVkResult<Self> {
+        let query_pool = {
+            let create_info = vk::QueryPoolCreateInfo::default()
+                .query_type(vk::QueryType::OCCLUSION)
+                .query_count(capacity);
+
+            let query_pool = unsafe {
+                vulkan
+                    .device()
+                    .create_query_pool(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+            }
+            .map_err(|e| VkError::new(e, "vkCreateQueryPool"))?;
+
+            unsafe { try_name(vulkan, query_pool, &format!("{label} Occlusion Query Pool")) };
+
+            query_pool
+        };
+
+        Ok(Self {
+            query_pool,
+            capacity,
+        })
+    }
+
+    /// Records resetting all queries, this **MUST** be recorded outside of a render pass before
+    /// any queries are begun.
+    pub unsafe fn cmd_reset<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+    ) {
+        unsafe {
+            vulkan
+                .device()
+                .cmd_reset_query_pool(command_buffer, self.query_pool, 0, self.capacity)
+        };
+    }
+
+    /// Records beginning the query at `index`. If `precise`, the query counts the exact number of
+    /// samples, else it may only report zero or non-zero.
+    ///
+    /// # Safety
+    /// * `index` **MUST** be less than `capacity`.
+    /// * If `precise`, the `occlusionQueryPrecise` feature **MUST** be enabled.
+    pub unsafe fn cmd_begin<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+        index: u32,
+        precise: bool,
+    ) {
+        let flags = if precise {
+            vk::QueryControlFlags::PRECISE
+        } else {
+            vk::QueryControlFlags::empty()
+        };
+
+        unsafe {
+            vulkan
+                .device()
+                .cmd_begin_query(command_buffer, self.query_pool, index, flags)
+        };
+    }
+
+    /// Records ending the query at `index`.
+    pub unsafe fn cmd_end<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+        index: u32,
+    ) {
+        unsafe {
+            vulkan
+                .device()
+                .cmd_end_query(command_buffer, self.query_pool, index)
+        };
+    }
+
+    /// Records copying the results of the `count` queries starting at `first_index` into `buffer`
+    /// at `offset` as tightly packed `u32`s, waiting for the results to be available. The copied
+    /// results can be used as predicates for [`cmd_begin_conditional_rendering`].
+    ///
+    /// # Safety
+    /// * `buffer` **MUST** have `vk::BufferUsageFlags::TRANSFER_DST` usage and be large enough to
+    ///   hold the results.
+    pub unsafe fn cmd_copy_results<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        command_buffer: vk::CommandBuffer,
+        first_index: u32,
+        count: u32,
+        buffer: vk::Buffer,
+        offset: vk::DeviceSize,
+    ) {
+        unsafe {
+            vulkan.device().cmd_copy_query_pool_results(
+                command_buffer,
+                self.query_pool,
+                first_index,
+                count,
+                buffer,
+                offset,
+                size_of::<u32>() as u64,
+                vk::QueryResultFlags::WAIT,
+            )
+        };
+    }
+
+    /// Waits for and reads the results of the `count` queries starting at `first_index`.
+    pub unsafe fn read_results<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        first_index: u32,
+        count: u32,
+    ) -> LabelledVkResult<Vec<u64>> {
+        let mut results = vec![0u64; count as usize];
+
+        if results.is_empty() {
+            return Ok(results);
+        }
+
+        unsafe {
+            vulkan.device().get_query_pool_results(
+                self.query_pool,
+                first_index,
+                &mut results,
+                vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WAIT,
+            )
+        }
+        .map_err(|e| VkError::new(e, "vkGetQueryPoolResults"))?;
+
+        Ok(results)
+    }
+
+    /// Destroys the query pool.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
+        unsafe {
+            vulkan
+                .device()
+                .destroy_query_pool(self.query_pool, VK_GLOBAL_ALLOCATOR.as_deref())
+        };
+    }
+}
+
+/// Records beginning conditional rendering, where subsequent draws and dispatches are discarded if
+/// the `u32` in `buffer` at `offset` is zero, or non-zero if `inverted`.
+///
+/// # Safety
+/// * `VK_EXT_conditional_rendering` **MUST** be enabled.
+/// * `buffer` **MUST** have `vk::BufferUsageFlags::CONDITIONAL_RENDERING_EXT` usage.
+/// * `offset` **MUST** be a multiple of 4.
+pub unsafe fn cmd_begin_conditional_rendering(
+    conditional_rendering: &ext::conditional_rendering::Device,
+    command_buffer: vk::CommandBuffer,
+    buffer: vk::Buffer,
+    offset: vk::DeviceSize,
+    inverted: bool,
+) {
+    let flags = if inverted {
+        vk::ConditionalRenderingFlagsEXT::INVERTED
+    } else {
+        vk::ConditionalRenderingFlagsEXT::empty()
+    };
+
+    let begin_info = vk::ConditionalRenderingBeginInfoEXT::default()
+        .buffer(buffer)
+        .offset(offset)
+        .flags(flags);
+
+    unsafe { conditional_rendering.cmd_begin_conditional_rendering(command_buffer, &begin_info) };
+}
+
+/// Records ending conditional rendering.
+///
+/// # Safety
+/// * `VK_EXT_conditional_rendering` **MUST** be enabled.
+pub unsafe fn cmd_end_conditional_rendering(
+    conditional_rendering: &ext::conditional_rendering::Device,
+    command_buffer: vk::CommandBuffer,
+) {
+    unsafe { conditional_rendering.cmd_end_conditional_rendering(command_buffer) };
+}
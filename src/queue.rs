@@ -0,0 +1,309 @@
+use std::sync::Mutex;
+
+use ash::{khr, vk};
+
+use crate::{
+    AllocationError, LabelledVkResult, MaybeMutex, StagingUploader, VK_GLOBAL_ALLOCATOR, VkError,
+    VulkanContext, chunked_upload, onetime_command, present_families,
+    unified_graphics_present_family,
+};
+
+/// The purpose a queue is requested for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueuePurpose {
+    /// Graphics, compute, and transfer work.
+    Graphics,
+    /// Compute work, preferring an async compute family.
+    Compute,
+    /// Transfer work, preferring a dedicated transfer family.
+    Transfer,
+    /// Presenting to a surface.
+    Present,
+}
+
+/// The queue family selected for each [`QueuePurpose`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueFamilies {
+    /// A family supporting graphics.
+    pub graphics: u32,
+    /// A family supporting compute without graphics, else `graphics`.
+    pub compute: u32,
+    /// A family supporting transfer without graphics or compute, else one without graphics, else
+    /// `graphics`.
+    pub transfer: u32,
+    /// A family that can present to the surface, preferring `graphics`. `None` if no surface was
+    /// given or no family can present to it.
+    pub present: Option<u32>,
+}
+
+impl QueueFamilies {
+    /// Selects a queue family for each purpose on `physical_device`, falling back to the graphics
+    /// family when no dedicated family exists. Returns `None` if no family supports graphics.
+    ///
+    /// The present family is only selected if `surface` is `Some`.
+    pub unsafe fn select(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+        surface: Option<(&khr::surface::Instance, vk::SurfaceKHR)>,
+    ) -> LabelledVkResult<Option<Self>> {
+        let families =
+            unsafe { instance.get_physical_device_queue_family_properties(physical_device) };
+
+        let find_family = |required: vk::QueueFlags, excluded: vk::QueueFlags| {
+            families
+                .iter()
+                .position(|family| {
+                    family.queue_count > 0
+                        && family.queue_flags.contains(required)
+                        && !family.queue_flags.intersects(excluded)
+                })
+                .map(|index| index as u32)
+        };
+
+        let Some(graphics) = find_family(vk::QueueFlags::GRAPHICS, vk::QueueFlags::empty()) else {
+            return Ok(None);
+        };
+
+        let compute =
+            find_family(vk::QueueFlags::COMPUTE, vk::QueueFlags::GRAPHICS).unwrap_or(graphics);
+
+        // Graphics and compute families implicitly support transfer.
+        let transfer = find_family(
+            vk::QueueFlags::TRANSFER,
+            vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE,
+        )
+        .or_else(|| find_family(vk::QueueFlags::COMPUTE, vk::QueueFlags::GRAPHICS))
+        .unwrap_or(graphics);
+
+        let present = match surface {
+            Some((surface_instance, surface)) => {
+                let supported = unsafe {
+                    present_families(instance, surface_instance, physical_device, surface)
+                }?;
+
+                if supported.contains(&graphics) {
+                    Some(graphics)
+                } else {
+                    let unified = unsafe {
+                        unified_graphics_present_family(
+                            instance,
+                            surface_instance,
+                            physical_device,
+                            surface,
+                        )
+                    }?;
+
+                    unified.or(supported.first().copied())
+                }
+            }
+
+            None => None,
+        };
+
+        Ok(Some(Self {
+            graphics,
+            compute,
+            transfer,
+            present,
+        }))
+    }
+
+    /// Returns the family selected for `purpose`, `None` if `purpose` is `Present` and no present
+    /// family was selected.
+    pub fn family_index(&self, purpose: QueuePurpose) -> Option<u32> {
+        match purpose {
+            QueuePurpose::Graphics => Some(self.graphics),
+            QueuePurpose::Compute => Some(self.compute),
+            QueuePurpose::Transfer => Some(self.transfer),
+            QueuePurpose::Present => self.present,
+        }
+    }
+
+    /// Returns the distinct selected families, for creating a `vk::DeviceQueueCreateInfo` per
+    /// family.
+    pub fn unique_families(&self) -> Vec<u32> {
+        let mut families = vec![self.graphics, self.compute, self.transfer];
+        families.extend(self.present);
+
+        families.sort_unstable();
+        families.dedup();
+
+        families
+    }
+}
+
+/// Routes requests for a queue by [`QueuePurpose`] to the first queue of the selected family,
+/// each queue is guarded by a mutex as purposes may share a queue. A `TRANSIENT` command pool is
+/// kept for each family, for onetime commands and uploads on the routed queue.
+pub struct QueueRouter {
+    /// The selected families.
+    pub families: QueueFamilies,
+    /// The first queue of each unique family.
+    pub queues: Vec<(u32, Mutex<vk::Queue>)>,
+    /// The command pool of each unique family.
+    pub command_pools: Vec<(u32, Mutex<vk::CommandPool>)>,
+}
+
+impl QueueRouter {
+    /// Gets the first queue of each unique family in `families` and creates a `TRANSIENT` command
+    /// pool for each family.
+    ///
+    /// # Safety
+    /// * `device` **MUST** have been created with at least one queue from each of
+    ///   [`QueueFamilies::unique_families`].
+    pub unsafe fn new(device: &ash::Device, families: QueueFamilies) -> LabelledVkResult<Self> {
+        let unique_families = families.unique_families();
+
+        let queues = unique_families
+            .iter()
+            .map(|&family_index| {
+                let queue = unsafe { device.get_device_queue(family_index, 0) };
+                (family_index, Mutex::new(queue))
+            })
+            .collect();
+
+        let mut router = Self {
+            families,
+            queues,
+            command_pools: Vec::with_capacity(unique_families.len()),
+        };
+
+        for family_index in unique_families {
+            let create_info = vk::CommandPoolCreateInfo::default()
+                .flags(vk::CommandPoolCreateFlags::TRANSIENT)
+                .queue_family_index(family_index);
+
+            let pool = match unsafe {
+                device.create_command_pool(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+            } {
+                Ok(pool) => pool,
+                Err(e) => {
+                    unsafe { router.destroy(device) };
+                    return Err(VkError::new(e, "vkCreateCommandPool"));
+                }
+            };
+
+            router.command_pools.push((family_index, Mutex::new(pool)));
+        }
+
+        Ok(router)
+    }
+
+    /// Returns the family index for `purpose`, `None` if `purpose` is `Present` and no present
+    /// family was selected.
+    pub fn family_index(&self, purpose: QueuePurpose) -> Option<u32> {
+        self.families.family_index(purpose)
+    }
+
+    /// Returns the queue for `purpose`, `None` if `purpose` is `Present` and no present family was
+    /// selected.
+    pub fn queue(&self, purpose: QueuePurpose) -> Option<&Mutex<vk::Queue>> {
+        let family_index = self.family_index(purpose)?;
+
+        self.queues
+            .iter()
+            .find(|(index, _)| *index == family_index)
+            .map(|(_, queue)| queue)
+    }
+
+    /// Returns the command pool for the family of `purpose`, `None` if `purpose` is `Present` and
+    /// no present family was selected.
+    pub fn command_pool(&self, purpose: QueuePurpose) -> Option<&Mutex<vk::CommandPool>> {
+        let family_index = self.family_index(purpose)?;
+
+        self.command_pools
+            .iter()
+            .find(|(index, _)| *index == family_index)
+            .map(|(_, pool)| pool)
+    }
+
+    /// Runs a onetime command on the queue for `purpose`, e.g., a dedicated transfer queue for
+    /// uploads, using the command pool of the queue's family. Returns `None` if `purpose` is
+    /// `Present` and no present family was selected.
+    ///
+    /// # Safety
+    /// * Resources used by `cmd_fn` that are owned by another queue family **MUST** be
+    ///   `CONCURRENT` or have their ownership transferred.
+    pub unsafe fn onetime_command<Vulkan, CmdFn>(
+        &self,
+        vulkan: &Vulkan,
+        purpose: QueuePurpose,
+        cmd_fn: CmdFn,
+        label: &str,
+    ) -> Option<LabelledVkResult<()>>
+    where
+        Vulkan: VulkanContext,
+        CmdFn: FnOnce(&Vulkan, vk::CommandBuffer),
+    {
+        let command_pool = self.command_pool(purpose)?;
+        let queue = self.queue(purpose)?;
+
+        Some(unsafe { onetime_command(vulkan, command_pool, queue, cmd_fn, label) })
+    }
+
+    /// Uploads `data` to `dst_buffer` like [`chunked_upload`] on the transfer queue.
+    ///
+    /// # Safety
+    /// * See [`chunked_upload`].
+    /// * `dst_buffer` **MUST** be `CONCURRENT` or owned by the transfer family.
+    pub unsafe fn chunked_upload<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        dst_buffer: vk::Buffer,
+        data: &[u8],
+        chunk_size: u64,
+        label: &str,
+    ) -> Result<(), AllocationError> {
+        let (command_pool, queue) = self.transfer();
+
+        unsafe {
+            chunked_upload(
+                vulkan,
+                command_pool,
+                queue,
+                dst_buffer,
+                data,
+                chunk_size,
+                label,
+            )
+        }
+    }
+
+    /// Submits the uploads queued in `uploader` like [`StagingUploader::submit`] on the transfer
+    /// queue.
+    ///
+    /// # Safety
+    /// * The destination buffers and images **MUST** be `CONCURRENT` or owned by the transfer
+    ///   family.
+    pub unsafe fn submit_uploads<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        uploader: &mut StagingUploader,
+        label: &str,
+    ) -> LabelledVkResult<()> {
+        let (command_pool, queue) = self.transfer();
+
+        unsafe { uploader.submit(vulkan, command_pool, queue, label) }
+    }
+
+    /// Destroys the command pools.
+    ///
+    /// # Safety
+    /// * Any submissions using command buffers from the pools **MUST** have completed.
+    pub unsafe fn destroy(&self, device: &ash::Device) {
+        for (_, pool) in &self.command_pools {
+            let (pool, _pool_guard) = MaybeMutex::from(pool).lock();
+            unsafe { device.destroy_command_pool(pool, VK_GLOBAL_ALLOCATOR.as_deref()) };
+        }
+    }
+
+    /// Returns the command pool and queue of the transfer family, which is always selected.
+    fn transfer(&self) -> (&Mutex<vk::CommandPool>, &Mutex<vk::Queue>) {
+        let command_pool = self.command_pool(QueuePurpose::Transfer);
+        let queue = self.queue(QueuePurpose::Transfer);
+
+        command_pool
+            .zip(queue)
+            .expect("The transfer family always has a queue and command pool")
+    }
+}
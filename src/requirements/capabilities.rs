@@ -0,0 +1,153 @@
+use alloc::ffi::CString;
+use core::{ffi::CStr, ptr};
+
+use ash::{ext, khr, vk};
+
+/// An optional device extension that code paths commonly branch on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Extension {
+    /// `VK_EXT_memory_priority`.
+    MemoryPriority,
+    /// `VK_EXT_pageable_device_local_memory`.
+    PageableDeviceLocalMemory,
+    /// `VK_EXT_shader_object`.
+    ShaderObject,
+    /// `VK_EXT_swapchain_maintenance1`.
+    SwapchainMaintenance1,
+    /// `VK_EXT_conditional_rendering`.
+    ConditionalRendering,
+    /// `VK_EXT_mesh_shader`.
+    MeshShader,
+    /// `VK_KHR_present_id`.
+    PresentId,
+    /// `VK_KHR_present_wait`.
+    PresentWait,
+}
+
+impl Extension {
+    /// The extension's name.
+    pub fn name(self) -> &'static CStr {
+        match self {
+            Self::MemoryPriority => ext::memory_priority::NAME,
+            Self::PageableDeviceLocalMemory => ext::pageable_device_local_memory::NAME,
+            Self::ShaderObject => ext::shader_object::NAME,
+            Self::SwapchainMaintenance1 => ext::swapchain_maintenance1::NAME,
+            Self::ConditionalRendering => ext::conditional_rendering::NAME,
+            Self::MeshShader => ext::mesh_shader::NAME,
+            Self::PresentId => khr::present_id::NAME,
+            Self::PresentWait => khr::present_wait::NAME,
+        }
+    }
+}
+
+/// A snapshot of a device's capabilities taken once at device creation, so optional code paths
+/// can branch without re-querying the physical device.
+#[derive(Debug, Clone)]
+pub struct DeviceCapabilities {
+    /// The physical device's API version.
+    pub api_version: u32,
+    /// The device extensions that were enabled.
+    pub extensions: Vec<CString>,
+    /// The physical device's properties, including its limits.
+    pub properties: vk::PhysicalDeviceProperties,
+    /// The physical device's supported Vulkan 1.0 features.
+    pub features: vk::PhysicalDeviceFeatures,
+    /// The physical device's supported Vulkan 1.1 features, default if the API version is below
+    /// 1.2.
+    pub vulkan_11_features: vk::PhysicalDeviceVulkan11Features<'static>,
+    /// The physical device's supported Vulkan 1.2 features, default if the API version is below
+    /// 1.2.
+    pub vulkan_12_features: vk::PhysicalDeviceVulkan12Features<'static>,
+    /// The physical device's supported Vulkan 1.3 features, default if the API version is below
+    /// 1.3.
+    pub vulkan_13_features: vk::PhysicalDeviceVulkan13Features<'static>,
+    /// The default subgroup size, zero if the API version is below 1.1.
+    pub subgroup_size: u32,
+}
+
+impl DeviceCapabilities {
+    /// Queries the capabilities of `physical_device`, recording `enabled_extensions` as the
+    /// device extensions that were enabled at device creation.
+    ///
+    /// The features are those supported by the physical device, only features that were enabled
+    /// at device creation may be used.
+    pub unsafe fn query(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+        enabled_extensions: &[&CStr],
+    ) -> Self {
+        let properties = unsafe { instance.get_physical_device_properties(physical_device) };
+        let api_version = properties.api_version;
+
+        let mut vulkan_11_features = vk::PhysicalDeviceVulkan11Features::default();
+        let mut vulkan_12_features = vk::PhysicalDeviceVulkan12Features::default();
+        let mut vulkan_13_features = vk::PhysicalDeviceVulkan13Features::default();
+        let mut subgroup_properties = vk::PhysicalDeviceSubgroupProperties::default();
+
+        let features = if api_version >= vk::API_VERSION_1_1 {
+            // Query the features
+            let mut features = vk::PhysicalDeviceFeatures2::default();
+            if api_version >= vk::API_VERSION_1_2 {
+                features = features
+                    .push_next(&mut vulkan_11_features)
+                    .push_next(&mut vulkan_12_features);
+            }
+            if api_version >= vk::API_VERSION_1_3 {
+                features = features.push_next(&mut vulkan_13_features);
+            }
+
+            unsafe { instance.get_physical_device_features2(physical_device, &mut features) };
+
+            // Query the subgroup properties
+            let mut properties2 =
+                vk::PhysicalDeviceProperties2::default().push_next(&mut subgroup_properties);
+
+            unsafe { instance.get_physical_device_properties2(physical_device, &mut properties2) };
+
+            features.features
+        } else {
+            unsafe { instance.get_physical_device_features(physical_device) }
+        };
+
+        // Clear the chain pointers into this stack frame
+        vulkan_11_features.p_next = ptr::null_mut();
+        vulkan_12_features.p_next = ptr::null_mut();
+        vulkan_13_features.p_next = ptr::null_mut();
+
+        Self {
+            api_version,
+            extensions: enabled_extensions
+                .iter()
+                .map(|&extension| extension.to_owned())
+                .collect(),
+            properties,
+            features,
+            vulkan_11_features,
+            vulkan_12_features,
+            vulkan_13_features,
+            subgroup_size: subgroup_properties.subgroup_size,
+        }
+    }
+
+    /// Returns if `extension` was enabled.
+    pub fn supports(&self, extension: Extension) -> bool {
+        self.supports_extension(extension.name())
+    }
+
+    /// Returns if the device extension `name` was enabled.
+    pub fn supports_extension(&self, name: &CStr) -> bool {
+        self.extensions
+            .iter()
+            .any(|extension| extension.as_c_str() == name)
+    }
+
+    /// Returns if the physical device's API version is at least `version`.
+    pub fn supports_api_version(&self, version: u32) -> bool {
+        self.api_version >= version
+    }
+
+    /// The physical device's limits.
+    pub fn limits(&self) -> &vk::PhysicalDeviceLimits {
+        &self.properties.limits
+    }
+}
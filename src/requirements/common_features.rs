@@ -0,0 +1,80 @@
+use ash::vk;
+
+/// The support for commonly required device features, queried in a single
+/// `vkGetPhysicalDeviceFeatures2` call.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CommonFeatures {
+    /// If `synchronization2` is supported.
+    pub synchronization2: bool,
+    /// If `timelineSemaphore` is supported.
+    pub timeline_semaphore: bool,
+    /// If `dynamicRendering` is supported.
+    pub dynamic_rendering: bool,
+    /// If `bufferDeviceAddress` is supported.
+    pub buffer_device_address: bool,
+
+    /// If `runtimeDescriptorArray` is supported.
+    pub runtime_descriptor_array: bool,
+    /// If `descriptorBindingPartiallyBound` is supported.
+    pub descriptor_binding_partially_bound: bool,
+    /// If `descriptorBindingVariableDescriptorCount` is supported.
+    pub descriptor_binding_variable_descriptor_count: bool,
+    /// If `descriptorBindingUpdateUnusedWhilePending` is supported.
+    pub descriptor_binding_update_unused_while_pending: bool,
+    /// If `descriptorBindingSampledImageUpdateAfterBind` is supported.
+    pub descriptor_binding_sampled_image_update_after_bind: bool,
+    /// If `shaderSampledImageArrayNonUniformIndexing` is supported.
+    pub shader_sampled_image_array_non_uniform_indexing: bool,
+    /// If `shaderStorageBufferArrayNonUniformIndexing` is supported.
+    pub shader_storage_buffer_array_non_uniform_indexing: bool,
+}
+
+impl CommonFeatures {
+    /// Queries the physical device's support for the common features.
+    ///
+    /// # Safety
+    /// * `physical_device` **MUST** support Vulkan 1.3, or the extension for each feature struct.
+    pub unsafe fn query(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> Self {
+        let mut synchronization2 = vk::PhysicalDeviceSynchronization2Features::default();
+        let mut timeline_semaphore = vk::PhysicalDeviceTimelineSemaphoreFeatures::default();
+        let mut dynamic_rendering = vk::PhysicalDeviceDynamicRenderingFeatures::default();
+        let mut buffer_device_address = vk::PhysicalDeviceBufferDeviceAddressFeatures::default();
+        let mut descriptor_indexing = vk::PhysicalDeviceDescriptorIndexingFeatures::default();
+
+        let mut features = vk::PhysicalDeviceFeatures2::default()
+            .push_next(&mut synchronization2)
+            .push_next(&mut timeline_semaphore)
+            .push_next(&mut dynamic_rendering)
+            .push_next(&mut buffer_device_address)
+            .push_next(&mut descriptor_indexing);
+
+        unsafe { instance.get_physical_device_features2(physical_device, &mut features) };
+
+        Self {
+            synchronization2: synchronization2.synchronization2 == vk::TRUE,
+            timeline_semaphore: timeline_semaphore.timeline_semaphore == vk::TRUE,
+            dynamic_rendering: dynamic_rendering.dynamic_rendering == vk::TRUE,
+            buffer_device_address: buffer_device_address.buffer_device_address == vk::TRUE,
+
+            runtime_descriptor_array: descriptor_indexing.runtime_descriptor_array == vk::TRUE,
+            descriptor_binding_partially_bound: descriptor_indexing
+                .descriptor_binding_partially_bound
+                == vk::TRUE,
+            descriptor_binding_variable_descriptor_count: descriptor_indexing
+                .descriptor_binding_variable_descriptor_count
+                == vk::TRUE,
+            descriptor_binding_update_unused_while_pending: descriptor_indexing
+                .descriptor_binding_update_unused_while_pending
+                == vk::TRUE,
+            descriptor_binding_sampled_image_update_after_bind: descriptor_indexing
+                .descriptor_binding_sampled_image_update_after_bind
+                == vk::TRUE,
+            shader_sampled_image_array_non_uniform_indexing: descriptor_indexing
+                .shader_sampled_image_array_non_uniform_indexing
+                == vk::TRUE,
+            shader_storage_buffer_array_non_uniform_indexing: descriptor_indexing
+                .shader_storage_buffer_array_non_uniform_indexing
+                == vk::TRUE,
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use core::ffi::CStr;
+
+use ash::vk;
+
+use crate::device_extension_properties;
+
+/// Returns if `physical_device` supports the device extension `name`.
+pub unsafe fn device_supports_extension(
+    instance: &ash::Instance,
+    physical_device: vk::PhysicalDevice,
+    name: &CStr,
+) -> bool {
+    let Ok(properties) =
+        (unsafe { instance.enumerate_device_extension_properties(physical_device) })
+    else {
+        return false;
+    };
+
+    properties
+        .iter()
+        .any(|property| property.extension_name_as_c_str() == Ok(name))
+}
+
+/// Returns if `physical_device` supports the device extension `name`, either from the
+/// implementation or from any of the enabled `layers`.
+pub unsafe fn device_supports_extension_with_layers(
+    instance: &ash::Instance,
+    physical_device: vk::PhysicalDevice,
+    name: &CStr,
+    layers: &[&CStr],
+) -> bool {
+    core::iter::once(None)
+        .chain(layers.iter().copied().map(Some))
+        .any(|layer_name| {
+            let Ok(properties) =
+                (unsafe { device_extension_properties(instance, physical_device, layer_name) })
+            else {
+                return false;
+            };
+
+            properties
+                .iter()
+                .any(|property| property.extension_name_as_c_str() == Ok(name))
+        })
+}
+
+/// The difference between the available and enabled extensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionDiff<'a> {
+    /// The extensions that are enabled and available.
+    pub enabled: Vec<&'a CStr>,
+    /// The extensions that are available but not enabled.
+    pub available_unused: Vec<&'a CStr>,
+    /// The extensions that are enabled but not available.
+    pub requested_unavailable: Vec<&'a CStr>,
+}
+
+/// Compares the `available` extensions against the `enabled` extensions, useful for logging the
+/// extension state in diagnostics.
+pub fn diff_extensions<'a>(available: &[&'a CStr], enabled: &[&'a CStr]) -> ExtensionDiff<'a> {
+    let (enabled_available, requested_unavailable): (Vec<_>, Vec<_>) = enabled
+        .iter()
+        .copied()
+        .partition(|extension| available.contains(extension));
+
+    let available_unused = available
+        .iter()
+        .copied()
+        .filter(|extension| !enabled.contains(extension))
+        .collect();
+
+    ExtensionDiff {
+        enabled: enabled_available,
+        available_unused,
+        requested_unavailable,
+    }
+}
@@ -0,0 +1,70 @@
+use ash::{khr, vk};
+
+use super::device_supports_extension;
+
+/// The availability of the `VK_KHR_maintenance1` to `VK_KHR_maintenance6` capabilities, either
+/// through the device's API version or the extension.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Maintenance {
+    /// If `VK_KHR_maintenance1` is available, core in Vulkan 1.1. Allows negative viewport heights
+    /// to flip the Y axis.
+    pub maintenance1: bool,
+    /// If `VK_KHR_maintenance2` is available, core in Vulkan 1.1.
+    pub maintenance2: bool,
+    /// If `VK_KHR_maintenance3` is available, core in Vulkan 1.1.
+    pub maintenance3: bool,
+    /// If `VK_KHR_maintenance4` is available, core in Vulkan 1.3.
+    pub maintenance4: bool,
+    /// If `VK_KHR_maintenance5` is available, core in Vulkan 1.4.
+    pub maintenance5: bool,
+    /// If `VK_KHR_maintenance6` is available, core in Vulkan 1.4.
+    pub maintenance6: bool,
+}
+
+impl Maintenance {
+    /// Queries the physical device's API version and extensions for the maintenance capabilities.
+    ///
+    /// The API version reported is the physical device's, the instance **MUST** also have been
+    /// created with at least that version for the promoted capabilities to be usable.
+    pub unsafe fn query(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> Self {
+        let api_version =
+            unsafe { instance.get_physical_device_properties(physical_device) }.api_version;
+
+        let is_available = |promoted_version: u32, extension| {
+            api_version >= promoted_version
+                || unsafe { device_supports_extension(instance, physical_device, extension) }
+        };
+
+        let api_version_1_4 = vk::make_api_version(0, 1, 4, 0);
+
+        Self {
+            maintenance1: is_available(vk::API_VERSION_1_1, khr::maintenance1::NAME),
+            maintenance2: is_available(vk::API_VERSION_1_1, khr::maintenance2::NAME),
+            maintenance3: is_available(vk::API_VERSION_1_1, khr::maintenance3::NAME),
+            maintenance4: is_available(vk::API_VERSION_1_3, khr::maintenance4::NAME),
+            maintenance5: is_available(api_version_1_4, khr::maintenance5::NAME),
+            maintenance6: is_available(api_version_1_4, khr::maintenance6::NAME),
+        }
+    }
+
+    /// Returns the highest maintenance level where it and all lower levels are available, zero if
+    /// `maintenance1` is unavailable.
+    pub fn level(&self) -> u32 {
+        [
+            self.maintenance1,
+            self.maintenance2,
+            self.maintenance3,
+            self.maintenance4,
+            self.maintenance5,
+            self.maintenance6,
+        ]
+        .into_iter()
+        .take_while(|&is_available| is_available)
+        .count() as u32
+    }
+
+    /// Returns if negative viewport heights are supported, allowing the Y axis to be flipped.
+    pub fn supports_negative_viewport(&self) -> bool {
+        self.maintenance1
+    }
+}
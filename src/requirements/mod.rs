@@ -0,0 +1,41 @@
+pub use capabilities::{DeviceCapabilities, Extension};
+pub use common_features::CommonFeatures;
+pub use extension::{
+    ExtensionDiff, device_supports_extension, device_supports_extension_with_layers,
+    diff_extensions,
+};
+pub use maintenance::Maintenance;
+pub use shader_object::ShaderObject;
+pub use sparse_binding::SparseBinding;
+pub use swapchain_maintenance1::SwapchainMaintenance1;
+pub use timeline_semaphore::TimelineSemaphore;
+
+use ash::vk;
+
+mod capabilities;
+mod common_features;
+mod extension;
+mod maintenance;
+mod shader_object;
+mod sparse_binding;
+mod swapchain_maintenance1;
+mod timeline_semaphore;
+
+/// A requirement that a physical device must meet to be suitable.
+pub trait DeviceRequirement {
+    /// Returns if `physical_device` meets the requirement.
+    unsafe fn validate_device(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+    ) -> bool;
+}
+
+/// A requirement that needs features from a structure extending `vk::PhysicalDeviceFeatures2`.
+pub trait RequiredFeatures2 {
+    /// The structure containing the required features.
+    type Features: vk::ExtendsPhysicalDeviceFeatures2 + vk::ExtendsDeviceCreateInfo;
+
+    /// Returns the structure with the required features enabled, for the caller to push onto the
+    /// `vk::DeviceCreateInfo`.
+    fn required_features() -> Self::Features;
+}
@@ -0,0 +1,38 @@
+use ash::{ext, vk};
+
+use super::{DeviceRequirement, RequiredFeatures2, device_supports_extension};
+
+/// Requires support for shader objects, see
+/// [`create_shader_objects`](crate::create_shader_objects).
+/// * `VK_EXT_shader_object`.
+/// * Features: `shaderObject`.
+pub struct ShaderObject;
+
+impl RequiredFeatures2 for ShaderObject {
+    type Features = vk::PhysicalDeviceShaderObjectFeaturesEXT<'static>;
+
+    fn required_features() -> Self::Features {
+        vk::PhysicalDeviceShaderObjectFeaturesEXT::default().shader_object(true)
+    }
+}
+
+impl DeviceRequirement for ShaderObject {
+    unsafe fn validate_device(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+    ) -> bool {
+        let is_available = unsafe {
+            device_supports_extension(instance, physical_device, ext::shader_object::NAME)
+        };
+        if !is_available {
+            return false;
+        }
+
+        let mut shader_object = vk::PhysicalDeviceShaderObjectFeaturesEXT::default();
+        let mut features = vk::PhysicalDeviceFeatures2::default().push_next(&mut shader_object);
+
+        unsafe { instance.get_physical_device_features2(physical_device, &mut features) };
+
+        shader_object.shader_object == vk::TRUE
+    }
+}
@@ -0,0 +1,41 @@
+use ash::vk;
+
+use super::DeviceRequirement;
+
+/// Requires support for sparse, partially resident buffers.
+/// * Features: `sparseBinding`, `sparseResidencyBuffer`.
+/// * A queue family supporting `vk::QueueFlags::SPARSE_BINDING`.
+pub struct SparseBinding;
+
+impl SparseBinding {
+    /// Enables the required features.
+    pub fn enable_features(features: vk::PhysicalDeviceFeatures) -> vk::PhysicalDeviceFeatures {
+        features.sparse_binding(true).sparse_residency_buffer(true)
+    }
+
+    /// Returns the first queue family on `physical_device` that supports sparse binding.
+    pub unsafe fn queue_family(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+    ) -> Option<u32> {
+        unsafe { instance.get_physical_device_queue_family_properties(physical_device) }
+            .iter()
+            .position(|family| family.queue_flags.contains(vk::QueueFlags::SPARSE_BINDING))
+            .map(|index| index as u32)
+    }
+}
+
+impl DeviceRequirement for SparseBinding {
+    unsafe fn validate_device(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+    ) -> bool {
+        let features = unsafe { instance.get_physical_device_features(physical_device) };
+
+        if features.sparse_binding != vk::TRUE || features.sparse_residency_buffer != vk::TRUE {
+            return false;
+        }
+
+        unsafe { Self::queue_family(instance, physical_device) }.is_some()
+    }
+}
@@ -0,0 +1,40 @@
+use ash::{ext, vk};
+
+use super::{DeviceRequirement, RequiredFeatures2, device_supports_extension};
+
+/// Requires support for swapchain maintenance 1, enabling present fences, deferred swapchain
+/// memory allocation, and switching present modes without a rebuild.
+/// * `VK_EXT_swapchain_maintenance1`, the instance **MUST** enable `VK_EXT_surface_maintenance1`.
+/// * Features: `swapchainMaintenance1`.
+pub struct SwapchainMaintenance1;
+
+impl RequiredFeatures2 for SwapchainMaintenance1 {
+    type Features = vk::PhysicalDeviceSwapchainMaintenance1FeaturesEXT<'static>;
+
+    fn required_features() -> Self::Features {
+        vk::PhysicalDeviceSwapchainMaintenance1FeaturesEXT::default().swapchain_maintenance1(true)
+    }
+}
+
+impl DeviceRequirement for SwapchainMaintenance1 {
+    unsafe fn validate_device(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+    ) -> bool {
+        let is_available = unsafe {
+            device_supports_extension(instance, physical_device, ext::swapchain_maintenance1::NAME)
+        };
+        if !is_available {
+            return false;
+        }
+
+        let mut swapchain_maintenance1 =
+            vk::PhysicalDeviceSwapchainMaintenance1FeaturesEXT::default();
+        let mut features =
+            vk::PhysicalDeviceFeatures2::default().push_next(&mut swapchain_maintenance1);
+
+        unsafe { instance.get_physical_device_features2(physical_device, &mut features) };
+
+        swapchain_maintenance1.swapchain_maintenance1 == vk::TRUE
+    }
+}
@@ -0,0 +1,41 @@
+use ash::{khr, vk};
+
+use super::{DeviceRequirement, RequiredFeatures2, device_supports_extension};
+
+/// Requires support for timeline semaphores.
+/// * Vulkan 1.2 or `VK_KHR_timeline_semaphore`.
+/// * Features: `timelineSemaphore`.
+pub struct TimelineSemaphore;
+
+impl RequiredFeatures2 for TimelineSemaphore {
+    type Features = vk::PhysicalDeviceTimelineSemaphoreFeatures<'static>;
+
+    fn required_features() -> Self::Features {
+        vk::PhysicalDeviceTimelineSemaphoreFeatures::default().timeline_semaphore(true)
+    }
+}
+
+impl DeviceRequirement for TimelineSemaphore {
+    unsafe fn validate_device(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+    ) -> bool {
+        let properties = unsafe { instance.get_physical_device_properties(physical_device) };
+
+        let is_available = properties.api_version >= vk::API_VERSION_1_2
+            || unsafe {
+                device_supports_extension(instance, physical_device, khr::timeline_semaphore::NAME)
+            };
+        if !is_available {
+            return false;
+        }
+
+        let mut timeline_semaphore = vk::PhysicalDeviceTimelineSemaphoreFeatures::default();
+        let mut features =
+            vk::PhysicalDeviceFeatures2::default().push_next(&mut timeline_semaphore);
+
+        unsafe { instance.get_physical_device_features2(physical_device, &mut features) };
+
+        timeline_semaphore.timeline_semaphore == vk::TRUE
+    }
+}
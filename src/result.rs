@@ -19,6 +19,67 @@ impl VkError {
     pub fn new(source: vk::Result, call: &'static str) -> Self {
         Self { call, source }
     }
+
+    /// The `vk::Result` returned by the call.
+    pub fn result(&self) -> vk::Result {
+        self.source
+    }
+
+    /// The name of the Vulkan call that failed.
+    pub fn call(&self) -> &'static str {
+        self.call
+    }
+
+    /// A plain-language explanation of the `vk::Result`, see [`explain_result`].
+    pub fn explanation(&self) -> &'static str {
+        explain_result(self.source)
+    }
+}
+
+/// Returns a plain-language explanation of `result` for displaying to end users.
+pub fn explain_result(result: vk::Result) -> &'static str {
+    match result {
+        vk::Result::SUCCESS => "The operation succeeded.",
+        vk::Result::ERROR_OUT_OF_HOST_MEMORY => {
+            "The system ran out of memory, try closing other applications."
+        }
+        vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => {
+            "The GPU ran out of memory, try closing other applications or lowering graphics settings."
+        }
+        vk::Result::ERROR_INITIALIZATION_FAILED => {
+            "The GPU driver failed to initialize, try updating your GPU drivers."
+        }
+        vk::Result::ERROR_DEVICE_LOST => {
+            "The GPU stopped responding, this may be caused by a driver crash or GPU reset, try updating your GPU drivers."
+        }
+        vk::Result::ERROR_LAYER_NOT_PRESENT => {
+            "A required Vulkan layer is not installed, try installing the Vulkan SDK or disabling debugging."
+        }
+        vk::Result::ERROR_EXTENSION_NOT_PRESENT => {
+            "The GPU or driver does not support a required extension, try updating your GPU drivers."
+        }
+        vk::Result::ERROR_FEATURE_NOT_PRESENT => {
+            "The GPU or driver does not support a required feature, try updating your GPU drivers."
+        }
+        vk::Result::ERROR_INCOMPATIBLE_DRIVER => {
+            "The GPU driver does not support the required Vulkan version, try updating your GPU drivers."
+        }
+        vk::Result::ERROR_FORMAT_NOT_SUPPORTED => "The GPU does not support a required format.",
+        vk::Result::ERROR_SURFACE_LOST_KHR => {
+            "The window surface was lost, try restarting the application."
+        }
+        vk::Result::ERROR_OUT_OF_DATE_KHR => {
+            "The window changed and the swapchain must be recreated."
+        }
+        vk::Result::ERROR_NATIVE_WINDOW_IN_USE_KHR => {
+            "The window is already in use by another graphics API."
+        }
+        vk::Result::ERROR_FRAGMENTED_POOL | vk::Result::ERROR_OUT_OF_POOL_MEMORY => {
+            "A descriptor pool ran out of space."
+        }
+        vk::Result::ERROR_TOO_MANY_OBJECTS => "Too many Vulkan objects of a type were created.",
+        _ => "An unexpected Vulkan error occurred, try updating your GPU drivers.",
+    }
 }
 
 impl Display for VkError {
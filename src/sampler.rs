@@ -0,0 +1,61 @@
+use ash::vk;
+
+use crate::{LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, try_name};
+
+/// Creates a sampler, with the reduction mode set if `reduction_mode` is `Some`.
+///
+/// Returns `ERROR_FEATURE_NOT_PRESENT` if a `MIN` or `MAX` reduction mode is requested but the
+/// device does not support `filterMinmaxSingleComponentFormats`.
+///
+/// # Safety
+/// * If `reduction_mode` is `Some`, Vulkan 1.2 or `VK_EXT_sampler_filter_minmax` **MUST** be
+///   enabled, and for Vulkan 1.2 the `samplerFilterMinmax` feature **MUST** be enabled.
+pub unsafe fn create_sampler<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    create_info: &vk::SamplerCreateInfo<'_>,
+    reduction_mode: Option<vk::SamplerReductionMode>,
+    label: &str,
+) -> LabelledVkResult<vk::Sampler> {
+    let mut reduction_info = vk::SamplerReductionModeCreateInfo::default()
+        .reduction_mode(reduction_mode.unwrap_or(vk::SamplerReductionMode::WEIGHTED_AVERAGE));
+
+    let create_info = match reduction_mode {
+        Some(reduction_mode) => {
+            // Validate min/max filtering support
+            if reduction_mode != vk::SamplerReductionMode::WEIGHTED_AVERAGE {
+                let mut minmax_properties =
+                    vk::PhysicalDeviceSamplerFilterMinmaxProperties::default();
+                let mut properties =
+                    vk::PhysicalDeviceProperties2::default().push_next(&mut minmax_properties);
+
+                unsafe {
+                    vulkan
+                        .instance()
+                        .get_physical_device_properties2(vulkan.physical_device(), &mut properties)
+                };
+
+                if minmax_properties.filter_minmax_single_component_formats != vk::TRUE {
+                    return Err(VkError::new(
+                        vk::Result::ERROR_FEATURE_NOT_PRESENT,
+                        "vkCreateSampler",
+                    ));
+                }
+            }
+
+            create_info.push_next(&mut reduction_info)
+        }
+
+        None => *create_info,
+    };
+
+    let sampler = unsafe {
+        vulkan
+            .device()
+            .create_sampler(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+    }
+    .map_err(|e| VkError::new(e, "vkCreateSampler"))?;
+
+    unsafe { try_name(vulkan, sampler, &format!("{label} Sampler")) };
+
+    Ok(sampler)
+}
@@ -0,0 +1,340 @@
+use core::{
+    slice,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use ash::vk;
+
+use crate::{
+    LabelledVkResult, MaybeMutex, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, fences_are_signaled,
+    logging::error,
+    queue_try_begin_label, queue_try_end_label,
+    requirements::{self, DeviceRequirement},
+    try_name,
+};
+
+/// A pool of binary semaphores for reuse between submissions.
+///
+/// Semaphores cannot be queried for their state, so a recycled semaphore is only reused once the
+/// fence of a submission that waits on it has signalled.
+pub struct SemaphorePool {
+    /// The semaphores that are free to be used.
+    pub free_semaphores: Vec<vk::Semaphore>,
+
+    /// The recycled semaphores and the fence that signals once they are no longer in use.
+    pub pending_semaphores: Vec<(vk::Fence, vk::Semaphore)>,
+
+    /// The number of semaphores the pool has created.
+    pub semaphore_count: usize,
+}
+
+impl SemaphorePool {
+    #[allow(clippy::new_without_default)]
+    /// Create a new instance of Self.
+    pub fn new() -> Self {
+        Self {
+            free_semaphores: vec![],
+            pending_semaphores: vec![],
+            semaphore_count: 0,
+        }
+    }
+
+    /// Get a free semaphore else create a new one. The semaphore **MUST** be returned to the pool
+    /// via [`Self::recycle`].
+    pub fn acquire<Vulkan: VulkanContext>(
+        &mut self,
+        vulkan: &Vulkan,
+    ) -> LabelledVkResult<vk::Semaphore> {
+        match self.free_semaphores.pop() {
+            Some(semaphore) => Ok(semaphore),
+
+            None => {
+                let create_info = vk::SemaphoreCreateInfo::default();
+
+                let semaphore = unsafe {
+                    vulkan
+                        .device()
+                        .create_semaphore(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+                        .map_err(|e| VkError::new(e, "vkCreateSemaphore"))?
+                };
+
+                unsafe {
+                    try_name(
+                        vulkan,
+                        semaphore,
+                        &format!("Semaphore Pool Semaphore {}", self.semaphore_count),
+                    )
+                };
+
+                self.semaphore_count += 1;
+
+                Ok(semaphore)
+            }
+        }
+    }
+
+    /// Return a semaphore to the pool, it will be reused once `fence` has signalled.
+    ///
+    /// # Safety
+    /// * `fence` **MUST** be signalled by a submission that waits on `semaphore`, or by a later
+    ///   submission on the same queue.
+    /// * `semaphore` **MUST** be unsignalled with no pending signal once `fence` has signalled.
+    /// * `fence` **MUST NOT** be reset or destroyed until the pool has been processed.
+    pub unsafe fn recycle(&mut self, semaphore: vk::Semaphore, fence: vk::Fence) {
+        self.pending_semaphores.push((fence, semaphore));
+    }
+
+    /// Make the recycled semaphores whose fence has signalled free to be acquired.
+    pub fn process<Vulkan: VulkanContext>(&mut self, vulkan: &Vulkan) -> LabelledVkResult<()> {
+        let mut length = self.pending_semaphores.len();
+        let mut index = 0;
+        while index < length {
+            let is_signaled = unsafe {
+                fences_are_signaled(vulkan, slice::from_ref(&self.pending_semaphores[index].0))
+                    .map_err(|e| VkError::new(e, "vkWaitForFences"))?
+            };
+
+            if is_signaled {
+                // The current index has been replaced with the last item, thus current index
+                // should not change.
+                let (_, semaphore) = self.pending_semaphores.swap_remove(index);
+                self.free_semaphores.push(semaphore);
+
+                // An item has been removed from the vec, thus the length should be decremented.
+                length -= 1;
+            } else {
+                // Move to the next item
+                index += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Waits for the device to be idle, then destroys all semaphores in the pool.
+    pub fn destroy<Vulkan: VulkanContext>(&mut self, vulkan: &Vulkan) {
+        if let Err(e) = unsafe { vulkan.device().device_wait_idle() } {
+            error!("Destroy SemaphorePool: Failed to wait for device idle: {e}");
+            return;
+        }
+
+        self.free_semaphores
+            .drain(..)
+            .chain(
+                self.pending_semaphores
+                    .drain(..)
+                    .map(|(_, semaphore)| semaphore),
+            )
+            .for_each(|semaphore| unsafe {
+                vulkan
+                    .device()
+                    .destroy_semaphore(semaphore, VK_GLOBAL_ALLOCATOR.as_deref())
+            });
+    }
+}
+
+/// A timeline semaphore that tracks the value of its latest pending signal, so each submission
+/// can wait on the previous one and signal the next value without hand-rolled bookkeeping.
+pub struct TimelineSemaphore {
+    /// The timeline semaphore.
+    pub semaphore: vk::Semaphore,
+    /// The value of the latest signal handed out.
+    pub value: AtomicU64,
+}
+
+impl TimelineSemaphore {
+    /// Creates a new timeline semaphore with `initial_value`.
+    ///
+    /// # Safety
+    /// * The device **MUST** have the `timelineSemaphore` feature enabled, see
+    ///   [`requirements::TimelineSemaphore`].
+    pub unsafe fn new<Vulkan: VulkanContext>(
+        vulkan: &Vulkan,
+        initial_value: u64,
+        label: &str,
+    ) -> LabelledVkResult<Self> {
+        debug_assert!(
+            unsafe {
+                requirements::TimelineSemaphore::validate_device(
+                    vulkan.instance(),
+                    vulkan.physical_device(),
+                )
+            },
+            "The physical device does not support timeline semaphores"
+        );
+
+        let mut type_info = vk::SemaphoreTypeCreateInfo::default()
+            .semaphore_type(vk::SemaphoreType::TIMELINE)
+            .initial_value(initial_value);
+        let create_info = vk::SemaphoreCreateInfo::default().push_next(&mut type_info);
+
+        let semaphore = unsafe {
+            vulkan
+                .device()
+                .create_semaphore(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+        }
+        .map_err(|e| VkError::new(e, "vkCreateSemaphore"))?;
+
+        unsafe { try_name(vulkan, semaphore, &format!("{label} Timeline Semaphore")) };
+
+        Ok(Self {
+            semaphore,
+            value: AtomicU64::new(initial_value),
+        })
+    }
+
+    /// Hands out the next `(wait, signal)` pair, where `wait` is the value of the previous signal
+    /// and `signal` is the next value. Each pair is unique, even across threads.
+    ///
+    /// The `signal` value **MUST** be signalled by a submission or the host, else waits on later
+    /// values never complete.
+    pub fn next_values(&self) -> (u64, u64) {
+        let wait = self.value.fetch_add(1, Ordering::AcqRel);
+        (wait, wait + 1)
+    }
+
+    /// Returns the value of the latest signal handed out, which may still be pending.
+    pub fn last_value(&self) -> u64 {
+        self.value.load(Ordering::Acquire)
+    }
+
+    /// Returns the current value of the semaphore on the device.
+    pub unsafe fn current_value<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+    ) -> LabelledVkResult<u64> {
+        unsafe { vulkan.device().get_semaphore_counter_value(self.semaphore) }
+            .map_err(|e| VkError::new(e, "vkGetSemaphoreCounterValue"))
+    }
+
+    /// Waits for the semaphore to reach `value`. Returns `false` if `timeout` elapsed first.
+    pub unsafe fn wait<Vulkan: VulkanContext>(
+        &self,
+        vulkan: &Vulkan,
+        value: u64,
+        timeout: Duration,
+    ) -> LabelledVkResult<bool> {
+        let wait_info = vk::SemaphoreWaitInfo::default()
+            .semaphores(slice::from_ref(&self.semaphore))
+            .values(slice::from_ref(&value));
+
+        let timeout = u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX);
+
+        match unsafe { vulkan.device().wait_semaphores(&wait_info, timeout) } {
+            Ok(()) => Ok(true),
+            Err(vk::Result::TIMEOUT) => Ok(false),
+            Err(e) => Err(VkError::new(e, "vkWaitSemaphores")),
+        }
+    }
+
+    /// Signals the semaphore with the next value from the host, returning the value.
+    pub unsafe fn signal<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) -> LabelledVkResult<u64> {
+        let (_, signal) = self.next_values();
+
+        let signal_info = vk::SemaphoreSignalInfo::default()
+            .semaphore(self.semaphore)
+            .value(signal);
+
+        unsafe { vulkan.device().signal_semaphore(&signal_info) }
+            .map_err(|e| VkError::new(e, "vkSignalSemaphore"))?;
+
+        Ok(signal)
+    }
+
+    /// Submits `command_buffers`, signalling the next value, which is returned. If `wait_stage` is
+    /// some, the submission waits at that stage for the previous value.
+    ///
+    /// If the submission fails, the values are handed back so later submissions do not wait on a
+    /// value that is never signalled.
+    ///
+    /// # Safety
+    /// * The device **MUST** have the `timelineSemaphore` feature enabled, see
+    ///   [`requirements::TimelineSemaphore`].
+    /// * Each command buffer **MUST** be in the executable state.
+    pub unsafe fn submit<'m, Vulkan, Queue>(
+        &self,
+        vulkan: &Vulkan,
+        queue: Queue,
+        command_buffers: &[vk::CommandBuffer],
+        wait_stage: Option<vk::PipelineStageFlags>,
+        label: &str,
+    ) -> LabelledVkResult<u64>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    {
+        let (queue, _queue_guard) = queue.into().lock();
+
+        // Take the values while the queue is locked, so submissions to the queue are in value
+        // order.
+        let (wait_value, signal_value) = self.next_values();
+
+        let (wait_semaphores, wait_values, wait_stages): (&[_], &[_], &[_]) = match &wait_stage {
+            Some(wait_stage) => (
+                slice::from_ref(&self.semaphore),
+                slice::from_ref(&wait_value),
+                slice::from_ref(wait_stage),
+            ),
+            None => (&[], &[], &[]),
+        };
+
+        let mut timeline_info = vk::TimelineSemaphoreSubmitInfo::default()
+            .wait_semaphore_values(wait_values)
+            .signal_semaphore_values(slice::from_ref(&signal_value));
+
+        let submit_info = vk::SubmitInfo::default()
+            .wait_semaphores(wait_semaphores)
+            .wait_dst_stage_mask(wait_stages)
+            .command_buffers(command_buffers)
+            .signal_semaphores(slice::from_ref(&self.semaphore))
+            .push_next(&mut timeline_info);
+
+        unsafe { queue_try_begin_label(vulkan, queue, label) };
+
+        let result = unsafe {
+            vulkan
+                .device()
+                .queue_submit(queue, slice::from_ref(&submit_info), vk::Fence::null())
+        }
+        .map_err(|e| VkError::new(e, "vkQueueSubmit"));
+
+        unsafe { queue_try_end_label(vulkan, queue) };
+
+        if let Err(e) = result {
+            // Roll back the values, unless a later value has already been handed out.
+            if self
+                .value
+                .compare_exchange(
+                    signal_value,
+                    wait_value,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                )
+                .is_err()
+            {
+                error!(
+                    "TimelineSemaphore submit: Failed to roll back value {signal_value}, a later value has been handed out"
+                );
+            }
+
+            return Err(e);
+        }
+
+        Ok(signal_value)
+    }
+
+    /// Waits for the latest signal handed out, then destroys the semaphore.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
+        if let Err(e) = unsafe { self.wait(vulkan, self.last_value(), Duration::MAX) } {
+            error!("Destroy TimelineSemaphore: Failed to wait for latest signal: {e}");
+            return;
+        }
+
+        unsafe {
+            vulkan
+                .device()
+                .destroy_semaphore(self.semaphore, VK_GLOBAL_ALLOCATOR.as_deref())
+        };
+    }
+}
@@ -1,6 +1,8 @@
 use ash::{ext, util::read_spv, vk};
 
-use crate::{Context, LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, try_name};
+use crate::{
+    Context, LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, as_bytes, try_name,
+};
 
 /// Creates a shader module from some SPV bytes.
 ///
@@ -59,6 +61,52 @@ where
     unsafe { create_shader_objects(vulkan, create_infos, name) }
 }
 
+/// Creates linked shader objects from the SPV bytes of each stage, using the `main` entry point,
+/// cleaning up any created shader objects on failure.
+///
+/// The stages **MUST** be in pipeline order, see [`link_shader_objects`].
+///
+/// # Panics
+/// - If the `read_spv` call fails on any of the bytes.
+///
+/// # Safety
+/// - Each stage's bytes **must** be valid SPV according to <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkShaderCreateInfoEXT.html>.
+pub unsafe fn create_linked_shaders_from_spv<Vulkan>(
+    vulkan: &Vulkan,
+    stages: &[(vk::ShaderStageFlags, &[u8])],
+    set_layouts: &[vk::DescriptorSetLayout],
+    push_constant_ranges: &[vk::PushConstantRange],
+    name: &str,
+) -> Result<Vec<vk::ShaderEXT>, vk::Result>
+where
+    Vulkan: Context<ext::shader_object::Device>,
+{
+    // SPIR-V code must be 4 byte aligned, so read into words first.
+    let codes: Vec<Vec<u32>> = stages
+        .iter()
+        .map(|(_, bytes)| {
+            let mut cursor = std::io::Cursor::new(bytes);
+            read_spv(&mut cursor).expect("Failed to read spv")
+        })
+        .collect();
+
+    let mut create_infos: Vec<_> = stages
+        .iter()
+        .zip(codes.iter())
+        .map(|((stage, _), code)| {
+            vk::ShaderCreateInfoEXT::default()
+                .stage(*stage)
+                .code_type(vk::ShaderCodeTypeEXT::SPIRV)
+                .code(unsafe { as_bytes(code) })
+                .name(c"main")
+                .set_layouts(set_layouts)
+                .push_constant_ranges(push_constant_ranges)
+        })
+        .collect();
+
+    unsafe { link_shader_objects(vulkan, &mut create_infos, name) }
+}
+
 /// Creates shader objects, cleaning up any created shader objects on failure.
 ///
 /// Each shader is named: `{name} {stage:?} SHADER`. E.g., `MAXIMUM REDUCTION COMPUTE SHADER`.
@@ -0,0 +1,157 @@
+use ash::vk;
+
+use crate::VulkanContext;
+
+/// Returns the largest workgroup size no larger than `desired` in each dimension that is within
+/// the device's `maxComputeWorkGroupSize` and `maxComputeWorkGroupInvocations`. Each dimension is
+/// at least `1`.
+pub fn recommended_workgroup_size<Vulkan: VulkanContext>(
+    vulkan: &Vulkan,
+    desired: [u32; 3],
+) -> [u32; 3] {
+    let limits = unsafe {
+        vulkan
+            .instance()
+            .get_physical_device_properties(vulkan.physical_device())
+    }
+    .limits;
+
+    clamp_workgroup_size(
+        desired,
+        limits.max_compute_work_group_size,
+        limits.max_compute_work_group_invocations,
+    )
+}
+
+/// Clamps each dimension of `size` to `max_size`, then reduces the largest dimension only as far
+/// as needed, until the invocation count is within `max_invocations`.
+fn clamp_workgroup_size(size: [u32; 3], max_size: [u32; 3], max_invocations: u32) -> [u32; 3] {
+    let mut size = [
+        size[0].clamp(1, max_size[0].max(1)),
+        size[1].clamp(1, max_size[1].max(1)),
+        size[2].clamp(1, max_size[2].max(1)),
+    ];
+
+    let max_invocations = u64::from(max_invocations.max(1));
+    let invocations = |size: [u32; 3]| u64::from(size[0]) * u64::from(size[1]) * u64::from(size[2]);
+
+    while invocations(size) > max_invocations {
+        let largest = (0..3).max_by_key(|&index| size[index]).unwrap_or(0);
+
+        // The largest size that fits alongside the other dimensions, if they already exceed the
+        // limit the next largest dimension is reduced on the next iteration.
+        let others = invocations(size) / u64::from(size[largest]);
+        size[largest] = (max_invocations / others).max(1) as u32;
+    }
+
+    size
+}
+
+/// The device's subgroup size range from `VK_EXT_subgroup_size_control`, core in Vulkan 1.3.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubgroupSizeControl {
+    /// The default subgroup size, shaders may run with any size in the range unless pinned.
+    pub subgroup_size: u32,
+    /// The minimum subgroup size.
+    pub min_subgroup_size: u32,
+    /// The maximum subgroup size.
+    pub max_subgroup_size: u32,
+    /// The maximum number of subgroups in a compute workgroup.
+    pub max_compute_workgroup_subgroups: u32,
+    /// The shader stages that support a required subgroup size.
+    pub required_subgroup_size_stages: vk::ShaderStageFlags,
+    /// If the `subgroupSizeControl` feature is supported, allowing the subgroup size to be pinned.
+    pub subgroup_size_control: bool,
+    /// If the `computeFullSubgroups` feature is supported, allowing compute workgroups to require
+    /// full subgroups.
+    pub compute_full_subgroups: bool,
+}
+
+impl SubgroupSizeControl {
+    /// Queries the device's subgroup size range.
+    ///
+    /// # Safety
+    /// * The physical device **MUST** support Vulkan 1.3 or `VK_EXT_subgroup_size_control`.
+    pub unsafe fn query<Vulkan: VulkanContext>(vulkan: &Vulkan) -> Self {
+        let mut subgroup_properties = vk::PhysicalDeviceSubgroupProperties::default();
+        let mut size_control_properties =
+            vk::PhysicalDeviceSubgroupSizeControlProperties::default();
+        let mut properties = vk::PhysicalDeviceProperties2::default()
+            .push_next(&mut subgroup_properties)
+            .push_next(&mut size_control_properties);
+
+        unsafe {
+            vulkan
+                .instance()
+                .get_physical_device_properties2(vulkan.physical_device(), &mut properties)
+        };
+
+        let mut size_control_features = vk::PhysicalDeviceSubgroupSizeControlFeatures::default();
+        let mut features =
+            vk::PhysicalDeviceFeatures2::default().push_next(&mut size_control_features);
+
+        unsafe {
+            vulkan
+                .instance()
+                .get_physical_device_features2(vulkan.physical_device(), &mut features)
+        };
+
+        Self {
+            subgroup_size: subgroup_properties.subgroup_size,
+            min_subgroup_size: size_control_properties.min_subgroup_size,
+            max_subgroup_size: size_control_properties.max_subgroup_size,
+            max_compute_workgroup_subgroups: size_control_properties
+                .max_compute_workgroup_subgroups,
+            required_subgroup_size_stages: size_control_properties.required_subgroup_size_stages,
+            subgroup_size_control: size_control_features.subgroup_size_control == vk::TRUE,
+            compute_full_subgroups: size_control_features.compute_full_subgroups == vk::TRUE,
+        }
+    }
+
+    /// Returns if `stage` can be pinned to `subgroup_size` with a
+    /// `vk::PipelineShaderStageRequiredSubgroupSizeCreateInfo`.
+    pub fn can_require_size(&self, stage: vk::ShaderStageFlags, subgroup_size: u32) -> bool {
+        self.subgroup_size_control
+            && self.required_subgroup_size_stages.contains(stage)
+            && subgroup_size.is_power_of_two()
+            && (self.min_subgroup_size..=self.max_subgroup_size).contains(&subgroup_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::clamp_workgroup_size;
+
+    #[test]
+    fn clamps_to_max_size() {
+        assert_eq!(
+            clamp_workgroup_size([2048, 0, 4], [1024, 1024, 64], 1024),
+            [256, 1, 4]
+        );
+        assert_eq!(
+            clamp_workgroup_size([8, 8, 128], [1024, 1024, 64], 1024),
+            [8, 8, 16]
+        );
+    }
+
+    #[test]
+    fn reduces_only_as_far_as_needed() {
+        assert_eq!(
+            clamp_workgroup_size([1024, 1, 1], [1024, 1024, 64], 768),
+            [768, 1, 1]
+        );
+        assert_eq!(
+            clamp_workgroup_size([32, 32, 1], [1024, 1024, 64], 1000),
+            [32, 31, 1]
+        );
+    }
+
+    #[test]
+    fn keeps_sizes_within_limits() {
+        assert_eq!(
+            clamp_workgroup_size([16, 16, 1], [1024, 1024, 64], 1024),
+            [16, 16, 1]
+        );
+        assert_eq!(clamp_workgroup_size([0, 0, 0], [0, 0, 0], 0), [1, 1, 1]);
+    }
+}
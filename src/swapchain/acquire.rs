@@ -4,7 +4,7 @@ use ash::vk;
 
 use crate::{LabelledVkResult, SurfaceContext, VkError, VulkanContext};
 
-use super::{FrameResources, Swapchain};
+use super::{FrameResources, Swapchain, surface_is_minimized};
 
 /// Resources of an acquired frame.
 pub struct Frame {
@@ -18,10 +18,32 @@ pub struct Frame {
     pub resources: FrameResources,
     /// If this image has been previously acquired.
     pub previously_acquired: bool,
+    /// The layout the image is presented in, `PRESENT_SRC_KHR` for a swapchain.
+    pub present_layout: vk::ImageLayout,
+}
+
+impl Frame {
+    /// The layout the image was left in by its previous use, `UNDEFINED` if this is the first
+    /// time the image has been acquired, else [`Self::present_layout`].
+    pub fn previous_layout(&self) -> vk::ImageLayout {
+        if self.previously_acquired {
+            self.present_layout
+        } else {
+            vk::ImageLayout::UNDEFINED
+        }
+    }
 }
 
 impl Swapchain {
     /// Acquire the next image from this swapchain and the resources to use.
+    ///
+    /// Errors that require recreating the surface or device can be identified with
+    /// [`SwapchainEvent::from_error`](crate::SwapchainEvent::from_error).
+    ///
+    /// Returns `None` without acquiring if the swapchain needs to be rebuilt and the surface is
+    /// minimized, see [`surface_is_minimized`]. The surface is only queried once the swapchain has
+    /// been flagged by an `OUT_OF_DATE` or `SUBOPTIMAL` result, or by setting `needs_to_rebuild`
+    /// when the window is resized.
     pub fn acquire_next_image<Vulkan, Surface>(
         &mut self,
         vulkan: &Vulkan,
@@ -32,6 +54,11 @@ impl Swapchain {
         Vulkan: VulkanContext,
         Surface: SurfaceContext,
     {
+        // Skip acquiring while minimized, a zero extent swapchain cannot be rebuilt.
+        if self.needs_to_rebuild && surface_is_minimized(vulkan, surface)? {
+            return Ok(None);
+        }
+
         // Get the resources
         let resources = self.next_resources(vulkan)?;
 
@@ -108,6 +135,7 @@ impl Swapchain {
             view,
             resources,
             previously_acquired,
+            present_layout: vk::ImageLayout::PRESENT_SRC_KHR,
         };
 
         if !previously_acquired {
@@ -135,3 +163,51 @@ impl Swapchain {
         Ok(resources)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ash::vk;
+
+    use super::{Frame, FrameResources};
+
+    fn frame(present_layout: vk::ImageLayout) -> Frame {
+        Frame {
+            image_index: 0,
+            image: vk::Image::null(),
+            view: vk::ImageView::null(),
+            resources: FrameResources {
+                acquire_semaphore: vk::Semaphore::null(),
+                render_semaphore: vk::Semaphore::null(),
+                render_fence: vk::Fence::null(),
+                command_pool: vk::CommandPool::null(),
+                command_buffer: vk::CommandBuffer::null(),
+                index: 0,
+            },
+            previously_acquired: false,
+            present_layout,
+        }
+    }
+
+    #[test]
+    fn first_acquisition_is_undefined() {
+        let frame = frame(vk::ImageLayout::PRESENT_SRC_KHR);
+        assert_eq!(frame.previous_layout(), vk::ImageLayout::UNDEFINED);
+    }
+
+    #[test]
+    fn reacquisition_is_present_layout() {
+        let mut swapchain_frame = frame(vk::ImageLayout::PRESENT_SRC_KHR);
+        swapchain_frame.previously_acquired = true;
+        assert_eq!(
+            swapchain_frame.previous_layout(),
+            vk::ImageLayout::PRESENT_SRC_KHR
+        );
+
+        let mut offscreen_frame = frame(vk::ImageLayout::TRANSFER_SRC_OPTIMAL);
+        offscreen_frame.previously_acquired = true;
+        assert_eq!(
+            offscreen_frame.previous_layout(),
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use core::slice;
+
+use ash::vk;
+
+use crate::{
+    AllocationError, MaybeMutex, VkError, VulkanContext, allocation::read_with,
+    cmd_copy_image_to_buffer, cmd_transition_image, commands::is_supported_layout,
+    copy_subresource_layers,
+};
+
+use super::{Frame, SwapchainInfo};
+
+/// The pixels of a captured frame.
+#[derive(Debug, Clone)]
+pub struct CapturedFrame {
+    /// The tightly packed `RGBA` pixels, 4 bytes per pixel.
+    pub pixels: Vec<u8>,
+    /// The extent of the frame.
+    pub extent: vk::Extent2D,
+    /// The format of the frame's image, `BGRA` formats have been swizzled to `RGBA` in `pixels`.
+    pub format: vk::Format,
+}
+
+/// Waits for the frame's submission to complete, then copies the image of `frame` into a
+/// temporary host-visible buffer and returns its pixels as `RGBA`, for screenshots and
+/// golden-image tests. Works for frames from a [`Swapchain`](super::Swapchain) or an
+/// [`OffscreenTarget`](super::OffscreenTarget).
+///
+/// The image is transitioned from `layout` for the copy then back to `layout`, call this after
+/// the frame has been submitted and before it is presented, usually with the frame's
+/// `present_layout`. A presented swapchain image may be in use by the presentation engine, so it
+/// cannot be captured.
+///
+/// Returns `None` without copying if the format is not an 8-bit `RGBA` or `BGRA` format, or if
+/// `layout` is not supported by [`crate::cmd_transition_image`] or is `UNDEFINED` or
+/// `PREINITIALIZED`.
+///
+/// # Safety
+/// * The image **MUST** have `TRANSFER_SRC` usage, a swapchain's `image_usage` **MUST** include
+///   it.
+/// * The frame's submission **MUST** signal its `render_fence` and leave the image in `layout`.
+/// * The frame **MUST NOT** have been presented.
+pub unsafe fn capture_frame<'m, Vulkan, Queue, Pool>(
+    vulkan: &Vulkan,
+    command_pool: Pool,
+    queue: Queue,
+    frame: &Frame,
+    info: &SwapchainInfo,
+    layout: vk::ImageLayout,
+    label: &str,
+) -> Result<Option<CapturedFrame>, AllocationError>
+where
+    Vulkan: VulkanContext,
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    Pool: Into<MaybeMutex<'m, vk::CommandPool>>,
+{
+    let format = info.format.format;
+    let extent = info.extent;
+    let image = frame.image;
+
+    let is_bgra = match format {
+        vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB => true,
+        vk::Format::R8G8B8A8_UNORM | vk::Format::R8G8B8A8_SRGB => false,
+        _ => return Ok(None),
+    };
+
+    // The image must be transitioned back to its layout.
+    if !is_supported_layout(layout)
+        || layout == vk::ImageLayout::UNDEFINED
+        || layout == vk::ImageLayout::PREINITIALIZED
+    {
+        return Ok(None);
+    }
+
+    // Wait for the frame's submission
+    unsafe {
+        vulkan.device().wait_for_fences(
+            slice::from_ref(&frame.resources.render_fence),
+            true,
+            u64::MAX,
+        )
+    }
+    .map_err(|e| VkError::new(e, "vkWaitForFences"))?;
+
+    let copy_fn = |vulkan: &Vulkan, command_buffer, buffer| unsafe {
+        cmd_transition_image(
+            vulkan,
+            command_buffer,
+            image,
+            layout,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+        );
+
+        cmd_copy_image_to_buffer(
+            vulkan,
+            command_buffer,
+            image,
+            vk::Extent3D {
+                width: extent.width,
+                height: extent.height,
+                depth: 1,
+            },
+            copy_subresource_layers(format, 0, 0, 1),
+            buffer,
+            0,
+        );
+
+        cmd_transition_image(
+            vulkan,
+            command_buffer,
+            image,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            layout,
+        );
+    };
+    let read_fn = |pixels: &[u8]| pixels.to_vec();
+
+    let count = extent.width as usize * extent.height as usize * 4;
+
+    let mut pixels = unsafe {
+        read_with(
+            vulkan,
+            (command_pool.into(), queue.into()),
+            count,
+            copy_fn,
+            read_fn,
+            &format!("{label} Capture"),
+        )
+    }?;
+
+    if is_bgra {
+        pixels
+            .chunks_exact_mut(4)
+            .for_each(|pixel| pixel.swap(0, 2));
+    }
+
+    Ok(Some(CapturedFrame {
+        pixels,
+        extent,
+        format,
+    }))
+}
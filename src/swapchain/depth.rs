@@ -0,0 +1,167 @@
+use ash::vk;
+
+use crate::{
+    AllocationError, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, allocate_image,
+    format_aspect_mask, format_properties, try_name,
+};
+
+/// The depth formats [`select_depth_format`] picks from, in order of preference.
+const DEPTH_FORMATS: [vk::Format; 3] = [
+    vk::Format::D32_SFLOAT,
+    vk::Format::D24_UNORM_S8_UINT,
+    vk::Format::D32_SFLOAT_S8_UINT,
+];
+
+/// Returns the preferred depth format that supports being a depth attachment with optimal
+/// tiling, `D32_SFLOAT` else `D24_UNORM_S8_UINT` else `D32_SFLOAT_S8_UINT`.
+pub fn select_depth_format<Vulkan: VulkanContext>(vulkan: &Vulkan) -> Option<vk::Format> {
+    DEPTH_FORMATS.into_iter().find(|&format| {
+        format_properties(vulkan, format)
+            .optimal_tiling_features
+            .contains(vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT)
+    })
+}
+
+/// A depth attachment matching a swapchain's extent, its memory, and its view. A companion to a
+/// [`Swapchain`](super::Swapchain) that is resized alongside it.
+#[derive(Debug, Clone, Copy)]
+pub struct DepthResources {
+    /// The depth image.
+    pub image: vk::Image,
+    /// The image's memory.
+    pub memory: vk::DeviceMemory,
+    /// The image's view.
+    pub view: vk::ImageView,
+    /// The image's format.
+    pub format: vk::Format,
+    /// The image's extent.
+    pub extent: vk::Extent2D,
+}
+
+impl DepthResources {
+    /// Creates a device-local depth attachment of `extent` and `format`, see
+    /// [`select_depth_format`].
+    ///
+    /// # Safety
+    /// * The image is left in `vk::ImageLayout::UNDEFINED` and **MUST** be transitioned before
+    ///   use.
+    pub unsafe fn new<Vulkan: VulkanContext>(
+        vulkan: &Vulkan,
+        extent: vk::Extent2D,
+        format: vk::Format,
+        label: &str,
+    ) -> Result<Self, AllocationError> {
+        let (image, memory, _) = {
+            let create_info = vk::ImageCreateInfo::default()
+                .image_type(vk::ImageType::TYPE_2D)
+                .format(format)
+                .extent(vk::Extent3D {
+                    width: extent.width,
+                    height: extent.height,
+                    depth: 1,
+                })
+                .mip_levels(1)
+                .array_layers(1)
+                .samples(vk::SampleCountFlags::TYPE_1)
+                .tiling(vk::ImageTiling::OPTIMAL)
+                .usage(vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT)
+                .sharing_mode(vk::SharingMode::EXCLUSIVE)
+                .initial_layout(vk::ImageLayout::UNDEFINED);
+
+            unsafe {
+                allocate_image(
+                    vulkan,
+                    &create_info,
+                    vk::MemoryPropertyFlags::DEVICE_LOCAL,
+                    &format!("{label} Depth"),
+                )
+            }?
+        };
+
+        let view = {
+            let create_info = vk::ImageViewCreateInfo::default()
+                .image(image)
+                .view_type(vk::ImageViewType::TYPE_2D)
+                .format(format)
+                .subresource_range(
+                    vk::ImageSubresourceRange::default()
+                        .aspect_mask(format_aspect_mask(format))
+                        .base_mip_level(0)
+                        .level_count(1)
+                        .base_array_layer(0)
+                        .layer_count(1),
+                );
+
+            let view = match unsafe {
+                vulkan
+                    .device()
+                    .create_image_view(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+            } {
+                Ok(view) => view,
+                Err(e) => {
+                    unsafe {
+                        vulkan
+                            .device()
+                            .destroy_image(image, VK_GLOBAL_ALLOCATOR.as_deref());
+                        vulkan
+                            .device()
+                            .free_memory(memory, VK_GLOBAL_ALLOCATOR.as_deref());
+                    }
+
+                    return Err(VkError::new(e, "vkCreateImageView").into());
+                }
+            };
+
+            unsafe { try_name(vulkan, view, &format!("{label} Depth Image View")) };
+
+            view
+        };
+
+        Ok(Self {
+            image,
+            memory,
+            view,
+            format,
+            extent,
+        })
+    }
+
+    /// Recreates the depth attachment with the same format if `extent` differs from its current
+    /// extent, e.g., after the swapchain has been rebuilt. Returns if it was recreated.
+    ///
+    /// # Safety
+    /// * If the extent differs, the current image **MUST NOT** be in use.
+    /// * A recreated image is left in `vk::ImageLayout::UNDEFINED`.
+    pub unsafe fn resize<Vulkan: VulkanContext>(
+        &mut self,
+        vulkan: &Vulkan,
+        extent: vk::Extent2D,
+        label: &str,
+    ) -> Result<bool, AllocationError> {
+        if self.extent == extent {
+            return Ok(false);
+        }
+
+        let resources = unsafe { Self::new(vulkan, extent, self.format, label) }?;
+
+        unsafe { self.destroy(vulkan) };
+        *self = resources;
+
+        Ok(true)
+    }
+
+    /// Destroys the view and image, and frees the memory.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
+        unsafe {
+            vulkan
+                .device()
+                .destroy_image_view(self.view, VK_GLOBAL_ALLOCATOR.as_deref());
+            vulkan
+                .device()
+                .destroy_image(self.image, VK_GLOBAL_ALLOCATOR.as_deref());
+            vulkan
+                .device()
+                .free_memory(self.memory, VK_GLOBAL_ALLOCATOR.as_deref());
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use ash::vk;
+
+use crate::VkError;
+
+/// The recovery action a swapchain error requires.
+///
+/// An out of date swapchain is not an error, acquiring and presenting flag it with
+/// `needs_to_rebuild` instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapchainEvent {
+    /// The surface has been lost and must be recreated, along with the swapchain.
+    RecreateSurface,
+
+    /// The device has been lost and must be recreated, along with all of its objects.
+    RecreateDevice,
+}
+
+impl SwapchainEvent {
+    /// Returns the recovery action for an error, or `None` if the error is not recoverable by
+    /// recreating the surface or device.
+    pub fn from_error(error: &VkError) -> Option<Self> {
+        match error.result() {
+            vk::Result::ERROR_SURFACE_LOST_KHR => Some(Self::RecreateSurface),
+            vk::Result::ERROR_DEVICE_LOST => Some(Self::RecreateDevice),
+            _ => None,
+        }
+    }
+}
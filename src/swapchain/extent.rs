@@ -0,0 +1,59 @@
+use ash::vk;
+
+use crate::{LabelledVkResult, SurfaceContext, VkError, VulkanContext};
+
+use super::clamp_extent;
+
+/// Queries the current extent of the surface. If the surface's extent is undefined, i.e., it is
+/// determined by the swapchain, `fallback` clamped by [`clamp_extent`] is returned instead, or a
+/// zero extent if the clamped extent is zero.
+pub fn current_extent<Vulkan, Surface>(
+    vulkan: &Vulkan,
+    surface: &Surface,
+    fallback: vk::Extent2D,
+) -> LabelledVkResult<vk::Extent2D>
+where
+    Vulkan: VulkanContext,
+    Surface: SurfaceContext,
+{
+    let capabilities = unsafe {
+        surface
+            .surface_instance()
+            .get_physical_device_surface_capabilities(vulkan.physical_device(), surface.surface())
+            .map_err(|e| VkError::new(e, "vkGetPhysicalDeviceSurfaceCapabilitiesKHR"))?
+    };
+
+    if capabilities.current_extent.width != u32::MAX
+        || capabilities.current_extent.height != u32::MAX
+    {
+        return Ok(capabilities.current_extent);
+    }
+
+    let extent = clamp_extent(fallback, &capabilities).unwrap_or_default();
+
+    Ok(extent)
+}
+
+/// Returns if the surface currently has a zero extent, e.g., the window is minimized. While
+/// minimized the swapchain cannot be rebuilt and rendering should be skipped until the extent is
+/// non-zero.
+pub fn surface_is_minimized<Vulkan, Surface>(
+    vulkan: &Vulkan,
+    surface: &Surface,
+) -> LabelledVkResult<bool>
+where
+    Vulkan: VulkanContext,
+    Surface: SurfaceContext,
+{
+    let capabilities = unsafe {
+        surface
+            .surface_instance()
+            .get_physical_device_surface_capabilities(vulkan.physical_device(), surface.surface())
+            .map_err(|e| VkError::new(e, "vkGetPhysicalDeviceSurfaceCapabilitiesKHR"))?
+    };
+
+    let is_minimized =
+        capabilities.current_extent.width == 0 || capabilities.current_extent.height == 0;
+
+    Ok(is_minimized)
+}
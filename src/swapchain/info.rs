@@ -15,10 +15,14 @@ pub struct SwapchainInfo {
     pub present_mode: vk::PresentModeKHR,
     /// The swapchain's image layers
     pub image_layers: u32,
+    /// If the present mode can be switched without rebuilding, see
+    /// [`Swapchain::set_present_mode`](super::Swapchain::set_present_mode).
+    pub present_mode_switching: bool,
 }
 
 impl SwapchainInfo {
-    /// Inherit swapchain info from create info and the swapchain image count.
+    /// Inherit swapchain info from create info and the swapchain image count, present mode
+    /// switching is left disabled.
     pub fn new(create_info: &vk::SwapchainCreateInfoKHR<'_>, image_count: usize) -> Self {
         Self {
             image_count,
@@ -29,6 +33,51 @@ impl SwapchainInfo {
             composite_alpha: create_info.composite_alpha,
             present_mode: create_info.present_mode,
             image_layers: create_info.image_array_layers,
+            present_mode_switching: false,
         }
     }
+
+    /// Returns which properties of this swapchain info differ from `old`. Useful after a rebuild
+    /// to only recreate the objects that depend on the changed properties.
+    pub fn changed_since(&self, old: &Self) -> SwapchainChanges {
+        SwapchainChanges {
+            image_count: self.image_count != old.image_count,
+            extent: self.extent.width != old.extent.width
+                || self.extent.height != old.extent.height,
+            format: self.format.format != old.format.format
+                || self.format.color_space != old.format.color_space,
+            composite_alpha: self.composite_alpha != old.composite_alpha,
+            present_mode: self.present_mode != old.present_mode,
+            image_layers: self.image_layers != old.image_layers,
+        }
+    }
+}
+
+/// Which properties changed between two swapchain infos.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SwapchainChanges {
+    /// If the number of images changed.
+    pub image_count: bool,
+    /// If the extent changed.
+    pub extent: bool,
+    /// If the surface format or colour space changed.
+    pub format: bool,
+    /// If the composite alpha changed.
+    pub composite_alpha: bool,
+    /// If the present mode changed.
+    pub present_mode: bool,
+    /// If the image layers changed.
+    pub image_layers: bool,
+}
+
+impl SwapchainChanges {
+    /// Returns if any property changed.
+    pub fn any(&self) -> bool {
+        self.image_count
+            || self.extent
+            || self.format
+            || self.composite_alpha
+            || self.present_mode
+            || self.image_layers
+    }
 }
@@ -0,0 +1,409 @@
+use core::slice;
+
+use ash::vk;
+
+use crate::{
+    AllocationError, LabelledVkResult, MaybeMutex, SurfaceContext, VK_GLOBAL_ALLOCATOR, VkError,
+    VulkanContext, cmd_transition_image, try_name,
+};
+
+use super::{DepthResources, Frame, Swapchain, SwapchainPreferences, SwapchainRetirement};
+
+/// Owns the active swapchain, the retirement for old swapchains, and a timeline semaphore
+/// counting completed frames, to acquire, record, submit, and present frames in one call.
+pub struct SwapchainManager {
+    /// The active swapchain.
+    pub swapchain: Swapchain,
+    /// The retirement for rebuilt swapchains.
+    pub retirement: SwapchainRetirement,
+    /// The preferences the swapchain is built with.
+    pub preferences: SwapchainPreferences,
+    /// The depth attachment matching the swapchain's extent, see [`Self::create_depth`].
+    pub depth: Option<DepthResources>,
+
+    /// Timeline semaphore that is signalled with a frame's number once its submission completes.
+    pub frame_semaphore: vk::Semaphore,
+    /// The number of frames that have been submitted.
+    pub frame_count: u64,
+}
+
+impl SwapchainManager {
+    /// Creates the swapchain from `preferences`. Returns `None` if the surface is minimized.
+    ///
+    /// # Safety
+    /// * The device **MUST** have the `timelineSemaphore` feature enabled, see
+    ///   [`TimelineSemaphore`](crate::requirements::TimelineSemaphore).
+    pub unsafe fn new<Vulkan, Surface>(
+        vulkan: &Vulkan,
+        surface: &Surface,
+        preferences: SwapchainPreferences,
+    ) -> LabelledVkResult<Option<Self>>
+    where
+        Vulkan: VulkanContext,
+        Surface: SurfaceContext,
+    {
+        let Some(create_info) = preferences.get_swapchain_create_info(vulkan, surface)? else {
+            return Ok(None);
+        };
+
+        let swapchain = unsafe { Swapchain::new(vulkan, surface, None, create_info) }?;
+
+        let frame_semaphore = {
+            let mut type_info = vk::SemaphoreTypeCreateInfo::default()
+                .semaphore_type(vk::SemaphoreType::TIMELINE)
+                .initial_value(0);
+            let create_info = vk::SemaphoreCreateInfo::default().push_next(&mut type_info);
+
+            let semaphore = match unsafe {
+                vulkan
+                    .device()
+                    .create_semaphore(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+            } {
+                Ok(semaphore) => semaphore,
+                Err(e) => {
+                    unsafe { swapchain.destroy(vulkan, surface) };
+                    return Err(VkError::new(e, "vkCreateSemaphore"));
+                }
+            };
+
+            unsafe { try_name(vulkan, semaphore, "Swapchain Frame Semaphore") };
+
+            semaphore
+        };
+
+        Ok(Some(Self {
+            swapchain,
+            retirement: SwapchainRetirement::new(),
+            preferences,
+            depth: None,
+            frame_semaphore,
+            frame_count: 0,
+        }))
+    }
+
+    /// Creates a depth attachment of `format` matching the swapchain's extent, see
+    /// [`select_depth_format`](super::select_depth_format). The manager owns the attachment, it is
+    /// resized when the swapchain is rebuilt and destroyed with the swapchain. Any previous depth
+    /// attachment is destroyed.
+    ///
+    /// # Safety
+    /// * Any previous depth attachment **MUST NOT** be in use.
+    pub unsafe fn create_depth<Vulkan: VulkanContext>(
+        &mut self,
+        vulkan: &Vulkan,
+        format: vk::Format,
+    ) -> Result<(), AllocationError> {
+        let depth = unsafe {
+            DepthResources::new(vulkan, self.swapchain.info.extent, format, "Swapchain")
+        }?;
+
+        if let Some(previous_depth) = self.depth.replace(depth) {
+            unsafe { previous_depth.destroy(vulkan) };
+        }
+
+        Ok(())
+    }
+
+    /// Processes the retirement, rebuilds the swapchain if needed, then acquires an image and
+    /// records `record_fn` into the frame's command buffer before submitting and presenting.
+    ///
+    /// The image is transitioned to `COLOR_ATTACHMENT_OPTIMAL` before `record_fn` and to
+    /// `PRESENT_SRC_KHR` after. `record_fn` is also given the depth attachment if one has been
+    /// created, its contents are not preserved between frames so it **MUST** be transitioned from
+    /// `UNDEFINED`. Returns if a frame was submitted, no frame is submitted while the
+    /// surface is minimized or the swapchain is out of date.
+    ///
+    /// # Safety
+    /// * The device **MUST** have the `timelineSemaphore` feature enabled, see
+    ///   [`TimelineSemaphore`](crate::requirements::TimelineSemaphore).
+    /// * `queue` **MUST** be from the context's queue family and support presenting to the
+    ///   surface.
+    pub unsafe fn frame<'m, Vulkan, Surface, Queue, RecordFn>(
+        &mut self,
+        vulkan: &Vulkan,
+        surface: &Surface,
+        queue: Queue,
+        record_fn: RecordFn,
+    ) -> Result<bool, AllocationError>
+    where
+        Vulkan: VulkanContext,
+        Surface: SurfaceContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>> + Copy,
+        RecordFn: FnOnce(vk::CommandBuffer, &Frame, Option<&DepthResources>),
+    {
+        self.retirement.process_retirement(vulkan, surface)?;
+
+        // Rebuild
+        if self.swapchain.needs_to_rebuild && !unsafe { self.rebuild(vulkan, surface) }? {
+            return Ok(false);
+        }
+
+        // Acquire
+        let frame = {
+            let acquire_fence = self.retirement.get_fence(vulkan)?;
+
+            let acquire_result = self
+                .swapchain
+                .acquire_next_image(vulkan, surface, acquire_fence);
+
+            let Some(frame) = track_acquire_fence(
+                &mut self.retirement,
+                self.swapchain.swapchain,
+                acquire_fence,
+                acquire_result,
+            )?
+            else {
+                return Ok(false);
+            };
+
+            frame
+        };
+
+        let command_buffer = frame.resources.command_buffer;
+
+        // Record
+        {
+            let cmd_fn = |vulkan: &Vulkan, command_buffer| unsafe {
+                cmd_transition_image(
+                    vulkan,
+                    command_buffer,
+                    frame.image,
+                    frame.previous_layout(),
+                    vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                );
+
+                record_fn(command_buffer, &frame, self.depth.as_ref());
+
+                cmd_transition_image(
+                    vulkan,
+                    command_buffer,
+                    frame.image,
+                    vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                    vk::ImageLayout::PRESENT_SRC_KHR,
+                );
+            };
+
+            unsafe { frame.resources.record(vulkan, cmd_fn) }?;
+        }
+
+        // Submit
+        {
+            let frame_number = self.frame_count + 1;
+
+            let wait_values = [0];
+            let signal_values = [0, frame_number];
+            let signal_semaphores = [frame.resources.render_semaphore, self.frame_semaphore];
+
+            let mut timeline_info = vk::TimelineSemaphoreSubmitInfo::default()
+                .wait_semaphore_values(&wait_values)
+                .signal_semaphore_values(&signal_values);
+
+            // The image layout transition must not happen before the image has been acquired.
+            let submit_info = vk::SubmitInfo::default()
+                .wait_semaphores(slice::from_ref(&frame.resources.acquire_semaphore))
+                .wait_dst_stage_mask(slice::from_ref(&vk::PipelineStageFlags::ALL_COMMANDS))
+                .command_buffers(slice::from_ref(&command_buffer))
+                .signal_semaphores(&signal_semaphores)
+                .push_next(&mut timeline_info);
+
+            let (raw_queue, _queue_guard) = queue.into().lock();
+            unsafe {
+                vulkan.device().queue_submit(
+                    raw_queue,
+                    slice::from_ref(&submit_info),
+                    frame.resources.render_fence,
+                )
+            }
+            .map_err(|e| VkError::new(e, "vkQueueSubmit"))?;
+
+            self.frame_count = frame_number;
+        }
+
+        // Present
+        self.swapchain.queue_present(
+            surface,
+            frame.image_index,
+            frame.resources.render_semaphore,
+            queue,
+        )?;
+
+        Ok(true)
+    }
+
+    /// Rebuilds the swapchain from the preferences and any present mode override, housing the old
+    /// swapchain in the retirement, see [`Swapchain::rebuild_if_needed`]. Returns `false` if the
+    /// surface is minimized and the swapchain could not be rebuilt.
+    ///
+    /// If the extent changed, the depth attachment is resized once the submitted frames have
+    /// completed.
+    pub unsafe fn rebuild<Vulkan, Surface>(
+        &mut self,
+        vulkan: &Vulkan,
+        surface: &Surface,
+    ) -> Result<bool, AllocationError>
+    where
+        Vulkan: VulkanContext,
+        Surface: SurfaceContext,
+    {
+        self.swapchain.needs_to_rebuild = true;
+
+        let is_rebuilt = unsafe {
+            self.swapchain.rebuild_if_needed(
+                vulkan,
+                surface,
+                &self.preferences,
+                &mut self.retirement,
+            )
+        }?;
+
+        // Resize the depth attachment once the frames using it have completed
+        let extent = self.swapchain.info.extent;
+        if let Some(depth) = self.depth.as_mut().filter(|depth| depth.extent != extent) {
+            let wait_info = vk::SemaphoreWaitInfo::default()
+                .semaphores(slice::from_ref(&self.frame_semaphore))
+                .values(slice::from_ref(&self.frame_count));
+
+            unsafe { vulkan.device().wait_semaphores(&wait_info, u64::MAX) }
+                .map_err(|e| VkError::new(e, "vkWaitSemaphores"))?;
+
+            unsafe { depth.resize(vulkan, extent, "Swapchain") }?;
+        }
+
+        Ok(is_rebuilt)
+    }
+
+    /// Waits for the device to be idle, then destroys the swapchain, the depth attachment, the
+    /// retirement, and the frame semaphore.
+    pub unsafe fn destroy<Vulkan, Surface>(&mut self, vulkan: &Vulkan, surface: &Surface)
+    where
+        Vulkan: VulkanContext,
+        Surface: SurfaceContext,
+    {
+        self.retirement.destroy(vulkan, surface);
+
+        unsafe {
+            self.swapchain.destroy(vulkan, surface);
+            if let Some(depth) = self.depth.take() {
+                depth.destroy(vulkan);
+            }
+            vulkan
+                .device()
+                .destroy_semaphore(self.frame_semaphore, VK_GLOBAL_ALLOCATOR.as_deref());
+        }
+    }
+}
+
+/// Tracks the acquisition of an acquired frame with `acquire_fence`, else returns the fence to the
+/// retirement's free fences, as an acquire that did not acquire an image leaves the fence unused.
+fn track_acquire_fence(
+    retirement: &mut SwapchainRetirement,
+    swapchain: vk::SwapchainKHR,
+    acquire_fence: vk::Fence,
+    acquire_result: LabelledVkResult<Option<Frame>>,
+) -> LabelledVkResult<Option<Frame>> {
+    match &acquire_result {
+        Ok(Some(frame)) => {
+            retirement.track_acquisition(swapchain, acquire_fence, frame.image_index);
+        }
+
+        Ok(None) | Err(_) => retirement.free_fences.push(acquire_fence),
+    }
+
+    acquire_result
+}
+
+#[cfg(test)]
+mod tests {
+    use ash::vk::{self, Handle};
+
+    use crate::{FrameResources, VkError};
+
+    use super::{Frame, SwapchainRetirement, track_acquire_fence};
+
+    fn frame(image_index: u32) -> Frame {
+        Frame {
+            image_index,
+            image: vk::Image::null(),
+            view: vk::ImageView::null(),
+            resources: FrameResources {
+                acquire_semaphore: vk::Semaphore::null(),
+                render_semaphore: vk::Semaphore::null(),
+                render_fence: vk::Fence::null(),
+                command_pool: vk::CommandPool::null(),
+                command_buffer: vk::CommandBuffer::null(),
+                index: 0,
+            },
+            previously_acquired: false,
+            present_layout: vk::ImageLayout::PRESENT_SRC_KHR,
+        }
+    }
+
+    #[test]
+    fn acquired_frame_tracks_fence() {
+        let mut retirement = SwapchainRetirement::new();
+        let swapchain = vk::SwapchainKHR::from_raw(1);
+        let fence = vk::Fence::from_raw(2);
+
+        let frame = track_acquire_fence(&mut retirement, swapchain, fence, Ok(Some(frame(3))))
+            .expect("the acquire succeeded")
+            .expect("a frame was acquired");
+
+        assert_eq!(frame.image_index, 3);
+        assert!(retirement.free_fences.is_empty());
+        assert_eq!(retirement.tracked_acquisitions.len(), 1);
+        assert_eq!(retirement.tracked_acquisitions[0].fence, fence);
+        assert_eq!(retirement.tracked_acquisitions[0].swapchain, swapchain);
+        assert_eq!(retirement.tracked_acquisitions[0].image_index, 3);
+    }
+
+    #[test]
+    fn reacquired_image_marks_previous_fence_as_garbage() {
+        let mut retirement = SwapchainRetirement::new();
+        let swapchain = vk::SwapchainKHR::from_raw(1);
+        let first_fence = vk::Fence::from_raw(2);
+        let second_fence = vk::Fence::from_raw(3);
+
+        let _ = track_acquire_fence(&mut retirement, swapchain, first_fence, Ok(Some(frame(0))));
+        let _ = track_acquire_fence(&mut retirement, swapchain, second_fence, Ok(Some(frame(0))));
+
+        assert_eq!(retirement.tracked_acquisitions.len(), 1);
+        assert_eq!(retirement.tracked_acquisitions[0].fence, second_fence);
+        assert_eq!(retirement.garbage_fences, [first_fence]);
+    }
+
+    #[test]
+    fn unacquired_frame_frees_fence() {
+        let mut retirement = SwapchainRetirement::new();
+        let fence = vk::Fence::from_raw(2);
+
+        let frame = track_acquire_fence(
+            &mut retirement,
+            vk::SwapchainKHR::from_raw(1),
+            fence,
+            Ok(None),
+        )
+        .expect("the acquire succeeded");
+
+        assert!(frame.is_none());
+        assert_eq!(retirement.free_fences, [fence]);
+        assert!(retirement.tracked_acquisitions.is_empty());
+    }
+
+    #[test]
+    fn failed_acquire_frees_fence() {
+        let mut retirement = SwapchainRetirement::new();
+        let fence = vk::Fence::from_raw(2);
+
+        let error = VkError::new(vk::Result::ERROR_DEVICE_LOST, "vkAcquireNextImageKHR");
+        let result = track_acquire_fence(
+            &mut retirement,
+            vk::SwapchainKHR::from_raw(1),
+            fence,
+            Err(error),
+        );
+
+        assert!(result.is_err());
+        assert_eq!(retirement.free_fences, [fence]);
+        assert!(retirement.tracked_acquisitions.is_empty());
+    }
+}
@@ -1,10 +1,18 @@
-use core::{fmt, slice};
+use core::{fmt, mem, slice};
 
 pub use acquire::Frame;
-pub use info::SwapchainInfo;
-pub use preferences::SwapchainPreferences;
+pub use capture::{CapturedFrame, capture_frame};
+pub use depth::{DepthResources, select_depth_format};
+pub use event::SwapchainEvent;
+pub use extent::{current_extent, surface_is_minimized};
+pub use info::{SwapchainChanges, SwapchainInfo};
+pub use manager::SwapchainManager;
+pub use offscreen::OffscreenTarget;
+pub use preferences::{SwapchainPreferences, clamp_extent, select_present_mode};
 pub use resources::FrameResources;
+pub use retired::RetiredSwapchain;
 pub use retirement::SwapchainRetirement;
+pub use target::RenderTarget;
 
 use ash::vk;
 
@@ -14,10 +22,18 @@ use crate::{
 };
 
 mod acquire;
+mod capture;
+mod depth;
+mod event;
+mod extent;
 mod info;
+mod manager;
+mod offscreen;
 mod preferences;
 mod resources;
+mod retired;
 mod retirement;
+mod target;
 
 /// A swapchain and associated resources and details.
 pub struct Swapchain {
@@ -25,6 +41,12 @@ pub struct Swapchain {
     pub needs_to_rebuild: bool,
     /// The swapchain info.
     pub info: SwapchainInfo,
+    /// The present modes this swapchain can switch between without being rebuilt, from a
+    /// `vk::SwapchainPresentModesCreateInfoEXT` in its create info. Empty if it cannot switch.
+    pub present_modes: Vec<vk::PresentModeKHR>,
+    /// The present mode set by [`Self::set_present_mode`], overrides the preferences when the
+    /// swapchain is rebuilt.
+    pub present_mode_override: Option<vk::PresentModeKHR>,
 
     /// The swapchain.
     pub swapchain: vk::SwapchainKHR,
@@ -46,6 +68,10 @@ pub struct Swapchain {
 
 impl Swapchain {
     /// Create a new swapchain for the surface with preferences.
+    ///
+    /// If `swapchain_create_info` chains a `vk::SwapchainPresentModesCreateInfoEXT`, the present
+    /// mode can be switched between its present modes by [`Self::set_present_mode`] without a
+    /// rebuild.
     pub unsafe fn new<Vulkan, Surface>(
         vulkan: &Vulkan,
         surface: &Surface,
@@ -62,6 +88,38 @@ impl Swapchain {
             swapchain_create_info
         };
 
+        // Find the switchable present modes
+        let present_modes = {
+            let mut next = swapchain_create_info
+                .p_next
+                .cast::<vk::BaseInStructure<'_>>();
+            let mut present_modes = vec![];
+
+            while let Some(structure) = unsafe { next.as_ref() } {
+                if structure.s_type == vk::StructureType::SWAPCHAIN_PRESENT_MODES_CREATE_INFO_EXT {
+                    let present_modes_info =
+                        unsafe { &*next.cast::<vk::SwapchainPresentModesCreateInfoEXT<'_>>() };
+
+                    present_modes = unsafe {
+                        slice::from_raw_parts(
+                            present_modes_info.p_present_modes,
+                            present_modes_info.present_mode_count as usize,
+                        )
+                    }
+                    .to_vec();
+                    break;
+                }
+
+                next = structure.p_next;
+            }
+
+            present_modes
+        };
+
+        let present_mode_override = old_swapchain
+            .as_ref()
+            .and_then(|swapchain| swapchain.present_mode_override);
+
         // Create swapchain
         let swapchain = unsafe {
             surface
@@ -132,11 +190,14 @@ impl Swapchain {
             }
         };
 
-        let info = SwapchainInfo::new(&swapchain_create_info, image_count);
+        let mut info = SwapchainInfo::new(&swapchain_create_info, image_count);
+        info.present_mode_switching = !present_modes.is_empty();
 
         Ok(Self {
             needs_to_rebuild: false,
             info,
+            present_modes,
+            present_mode_override,
 
             swapchain,
             images,
@@ -150,7 +211,95 @@ impl Swapchain {
         })
     }
 
+    /// Rebuilds the swapchain from `preferences` if it has been flagged as needing to, using it as
+    /// the `old_swapchain` and housing it in `retirement`. The present mode override is applied
+    /// and the switchable present modes are kept if they include the selected present mode.
+    ///
+    /// Returns if the swapchain was rebuilt, it is not rebuilt if it was not flagged or the
+    /// surface is minimized, in which case it remains flagged.
+    ///
+    /// If querying the surface fails, the swapchain is left unchanged. If creating the new
+    /// swapchain fails, the old swapchain may have been retired by the failed creation, so it is
+    /// housed in `retirement` and this is left without a swapchain, flagged to rebuild. It
+    /// **MUST** then be rebuilt before it is used.
+    pub unsafe fn rebuild_if_needed<Vulkan, Surface>(
+        &mut self,
+        vulkan: &Vulkan,
+        surface: &Surface,
+        preferences: &SwapchainPreferences,
+        retirement: &mut SwapchainRetirement,
+    ) -> LabelledVkResult<bool>
+    where
+        Vulkan: VulkanContext,
+        Surface: SurfaceContext,
+    {
+        if !self.needs_to_rebuild {
+            return Ok(false);
+        }
+
+        let overridden_preferences;
+        let preferences = match self.present_mode_override {
+            Some(present_mode) => {
+                overridden_preferences = preferences.clone().present_mode(vec![present_mode]);
+                &overridden_preferences
+            }
+            None => preferences,
+        };
+
+        let Some(create_info) = preferences.get_swapchain_create_info(vulkan, surface)? else {
+            return Ok(false);
+        };
+
+        let present_modes = self.present_modes.clone();
+        let mut present_modes_info =
+            vk::SwapchainPresentModesCreateInfoEXT::default().present_modes(&present_modes);
+
+        let create_info = if present_modes.contains(&create_info.present_mode) {
+            create_info.push_next(&mut present_modes_info)
+        } else {
+            create_info
+        };
+
+        let swapchain = match unsafe { Self::new(vulkan, surface, Some(self), create_info) } {
+            Ok(swapchain) => swapchain,
+            Err(e) => {
+                retirement.house_swapchain(self.retire_in_place());
+                return Err(e);
+            }
+        };
+
+        let old_swapchain = mem::replace(self, swapchain);
+        retirement.house_swapchain(old_swapchain.into_retired());
+
+        Ok(true)
+    }
+
+    /// Sets the present mode used by subsequent presents. Returns if the mode was switched without
+    /// a rebuild.
+    ///
+    /// If `mode` is one of [`Self::present_modes`], the mode is switched for the next present,
+    /// which requires `VK_EXT_swapchain_maintenance1`. Else the swapchain is flagged as needing to
+    /// rebuild with `mode`, falling back to `FIFO` if the surface does not support it.
+    pub fn set_present_mode(&mut self, mode: vk::PresentModeKHR) -> bool {
+        self.present_mode_override = Some(mode);
+
+        if self.info.present_mode == mode {
+            return true;
+        }
+
+        if self.present_modes.contains(&mode) {
+            self.info.present_mode = mode;
+            return true;
+        }
+
+        self.needs_to_rebuild = true;
+        false
+    }
+
     /// Queue a present operation for this swapchain.
+    ///
+    /// Errors that require recreating the surface or device can be identified with
+    /// [`SwapchainEvent::from_error`].
     pub fn queue_present<'m, Surface, Queue>(
         &mut self,
         surface: &Surface,
@@ -158,6 +307,51 @@ impl Swapchain {
         wait_semaphore: vk::Semaphore,
         queue: Queue,
     ) -> LabelledVkResult<()>
+    where
+        Surface: SurfaceContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    {
+        self.present(surface, image_index, wait_semaphore, queue, None)
+    }
+
+    /// Queue a present operation for this swapchain like [`Self::queue_present`], `present_fence`
+    /// signals once the image is no longer in use by the presentation engine. The fence can be
+    /// tracked by [`SwapchainRetirement::track_present`].
+    ///
+    /// # Safety
+    /// * `VK_EXT_swapchain_maintenance1` **MUST** be enabled, see
+    ///   [`SwapchainMaintenance1`](crate::requirements::SwapchainMaintenance1).
+    /// * `present_fence` **MUST** be unsignalled and not associated with any other queue operation.
+    pub unsafe fn queue_present_with_fence<'m, Surface, Queue>(
+        &mut self,
+        surface: &Surface,
+        image_index: u32,
+        wait_semaphore: vk::Semaphore,
+        queue: Queue,
+        present_fence: vk::Fence,
+    ) -> LabelledVkResult<()>
+    where
+        Surface: SurfaceContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    {
+        self.present(
+            surface,
+            image_index,
+            wait_semaphore,
+            queue,
+            Some(present_fence),
+        )
+    }
+
+    /// Queues the present operation, with an optional present fence.
+    fn present<'m, Surface, Queue>(
+        &mut self,
+        surface: &Surface,
+        image_index: u32,
+        wait_semaphore: vk::Semaphore,
+        queue: Queue,
+        present_fence: Option<vk::Fence>,
+    ) -> LabelledVkResult<()>
     where
         Surface: SurfaceContext,
         Queue: Into<MaybeMutex<'m, vk::Queue>>,
@@ -169,11 +363,25 @@ impl Swapchain {
 
         // Queue present
         let result = {
-            let present_info = vk::PresentInfoKHR::default()
+            let mut present_info = vk::PresentInfoKHR::default()
                 .image_indices(slice::from_ref(&image_index))
                 .swapchains(slice::from_ref(&self.swapchain))
                 .wait_semaphores(slice::from_ref(&wait_semaphore));
 
+            let mut fence_info = vk::SwapchainPresentFenceInfoEXT::default();
+            if let Some(present_fence) = present_fence.as_ref() {
+                fence_info = fence_info.fences(slice::from_ref(present_fence));
+                present_info = present_info.push_next(&mut fence_info);
+            }
+
+            // A switchable swapchain presents with the current present mode.
+            let mut present_mode_info = vk::SwapchainPresentModeInfoEXT::default();
+            if self.info.present_mode_switching {
+                present_mode_info =
+                    present_mode_info.present_modes(slice::from_ref(&self.info.present_mode));
+                present_info = present_info.push_next(&mut present_mode_info);
+            }
+
             let (queue, _queue_guard) = queue.into().lock();
             unsafe {
                 surface
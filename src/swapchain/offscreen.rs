@@ -0,0 +1,348 @@
+use core::{fmt, slice};
+
+use ash::vk;
+
+use crate::{
+    AllocationError, LabelledVkResult, MaybeMutex, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext,
+    allocate_image, try_name,
+};
+
+use super::{Frame, FrameResources, SwapchainInfo};
+
+/// A set of plain images that are acquired and "presented" like a [`Swapchain`](super::Swapchain),
+/// for rendering without a surface. Frames are the same [`Frame`] as a swapchain's, so recording
+/// code can be shared between on-screen and off-screen targets.
+///
+/// Acquiring signals the frame's `acquire_semaphore` and presenting waits on the given semaphore,
+/// each with an empty submission, so the semaphores are used exactly as they are for a swapchain.
+///
+/// `PRESENT_SRC_KHR` is only valid for swapchain images, frames are instead transitioned to the
+/// target's `present_layout` before they are presented, see [`Frame::previous_layout`].
+pub struct OffscreenTarget {
+    /// The target info, the present mode is always `FIFO`.
+    pub info: SwapchainInfo,
+    /// The layout frames are transitioned to before they are presented, in place of
+    /// `PRESENT_SRC_KHR`.
+    pub present_layout: vk::ImageLayout,
+
+    /// The images.
+    pub images: Vec<vk::Image>,
+    /// The images' memory.
+    pub memories: Vec<vk::DeviceMemory>,
+    /// The images' views.
+    pub views: Vec<vk::ImageView>,
+
+    /// The index of the current frame resources, which is also the index of the next image.
+    pub next_resources: usize,
+    /// The resources for each frame.
+    pub resources: Vec<FrameResources>,
+
+    /// The image indices this target has acquired.
+    pub acquired_images: Vec<u32>,
+    /// The image indices this target has presented.
+    pub presented_images: Vec<u32>,
+}
+
+impl OffscreenTarget {
+    /// Creates `image_count` device-local images of `extent` and `format` with `COLOR_ATTACHMENT`,
+    /// `TRANSFER_SRC`, and `usage` usage, their views, and the resources for each frame.
+    ///
+    /// Frames are presented in `present_layout`, e.g., `TRANSFER_SRC_OPTIMAL` for
+    /// [`capture_frame`](super::capture_frame), which **MUST NOT** be `PRESENT_SRC_KHR` unless
+    /// `VK_KHR_swapchain` is enabled.
+    ///
+    /// # Panics
+    /// * If `image_count` is zero.
+    pub unsafe fn new<Vulkan: VulkanContext>(
+        vulkan: &Vulkan,
+        extent: vk::Extent2D,
+        format: vk::Format,
+        image_count: usize,
+        usage: vk::ImageUsageFlags,
+        present_layout: vk::ImageLayout,
+        label: &str,
+    ) -> Result<Self, AllocationError> {
+        assert!(
+            image_count > 0,
+            "An OffscreenTarget must have at least one image"
+        );
+
+        let info = SwapchainInfo {
+            image_count,
+            extent,
+            format: vk::SurfaceFormatKHR::default()
+                .format(format)
+                .color_space(vk::ColorSpaceKHR::SRGB_NONLINEAR),
+            composite_alpha: vk::CompositeAlphaFlagsKHR::OPAQUE,
+            present_mode: vk::PresentModeKHR::FIFO,
+            image_layers: 1,
+            present_mode_switching: false,
+        };
+
+        let mut target = Self {
+            info,
+            present_layout,
+            images: Vec::with_capacity(image_count),
+            memories: Vec::with_capacity(image_count),
+            views: Vec::with_capacity(image_count),
+            next_resources: 0,
+            resources: Vec::with_capacity(image_count),
+            acquired_images: vec![],
+            presented_images: vec![],
+        };
+
+        for index in 0..image_count {
+            if let Err(e) = unsafe { target.create_frame(vulkan, index, usage, label) } {
+                unsafe { target.destroy(vulkan) };
+                return Err(e);
+            }
+        }
+
+        Ok(target)
+    }
+
+    /// Waits for the next frame's resources to be free, then signals its `acquire_semaphore` and
+    /// returns the frame. The image is always available, so unlike a swapchain this never returns
+    /// `None`.
+    ///
+    /// Like a swapchain, the frame's `render_fence` is only reset once the image has been
+    /// acquired.
+    ///
+    /// # Safety
+    /// * `queue` **MUST** be from the context's queue family.
+    pub unsafe fn acquire_next_image<'m, Vulkan, Queue>(
+        &mut self,
+        vulkan: &Vulkan,
+        queue: Queue,
+    ) -> LabelledVkResult<Frame>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    {
+        unsafe { self.acquire(vulkan, queue, vk::Fence::null()) }
+    }
+
+    /// Acquires the next frame, the submission signalling its `acquire_semaphore` also signals
+    /// `acquire_fence` if it is not null.
+    pub(super) unsafe fn acquire<'m, Vulkan, Queue>(
+        &mut self,
+        vulkan: &Vulkan,
+        queue: Queue,
+        acquire_fence: vk::Fence,
+    ) -> LabelledVkResult<Frame>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    {
+        let resources = self.resources[self.next_resources];
+        let image_index = self.next_resources as u32;
+
+        // Wait for the frame's previous submission, which also frees the image
+        unsafe {
+            vulkan.device().wait_for_fences(
+                slice::from_ref(&resources.render_fence),
+                true,
+                u64::MAX,
+            )
+        }
+        .map_err(|e| VkError::new(e, "vkWaitForFences"))?;
+
+        // Signal the acquire semaphore
+        {
+            let submit_info = vk::SubmitInfo::default()
+                .signal_semaphores(slice::from_ref(&resources.acquire_semaphore));
+
+            let (queue, _queue_guard) = queue.into().lock();
+            unsafe {
+                vulkan
+                    .device()
+                    .queue_submit(queue, slice::from_ref(&submit_info), acquire_fence)
+            }
+            .map_err(|e| VkError::new(e, "vkQueueSubmit"))?;
+        }
+
+        self.next_resources = (self.next_resources + 1) % self.resources.len();
+
+        unsafe {
+            vulkan
+                .device()
+                .reset_fences(slice::from_ref(&resources.render_fence))
+        }
+        .map_err(|e| VkError::new(e, "vkResetFences"))?;
+
+        let previously_acquired = self.acquired_images.contains(&image_index);
+
+        let frame = Frame {
+            image_index,
+            image: self.images[image_index as usize],
+            view: self.views[image_index as usize],
+            resources,
+            previously_acquired,
+            present_layout: self.present_layout,
+        };
+
+        if !previously_acquired {
+            self.acquired_images.push(image_index);
+        }
+
+        Ok(frame)
+    }
+
+    /// Waits on `wait_semaphore` in place of a present operation. The image is left in
+    /// [`Self::present_layout`], see [`Frame::previous_layout`].
+    ///
+    /// # Safety
+    /// * `queue` **MUST** be from the context's queue family.
+    /// * The image **MUST** be in [`Self::present_layout`] once `wait_semaphore` is signalled.
+    pub unsafe fn queue_present<'m, Vulkan, Queue>(
+        &mut self,
+        vulkan: &Vulkan,
+        image_index: u32,
+        wait_semaphore: vk::Semaphore,
+        queue: Queue,
+    ) -> LabelledVkResult<()>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    {
+        // Track the present history for this target
+        if !self.presented_images.contains(&image_index) {
+            self.presented_images.push(image_index);
+        }
+
+        let submit_info = vk::SubmitInfo::default()
+            .wait_semaphores(slice::from_ref(&wait_semaphore))
+            .wait_dst_stage_mask(slice::from_ref(&vk::PipelineStageFlags::BOTTOM_OF_PIPE));
+
+        let (queue, _queue_guard) = queue.into().lock();
+        unsafe {
+            vulkan
+                .device()
+                .queue_submit(queue, slice::from_ref(&submit_info), vk::Fence::null())
+        }
+        .map_err(|e| VkError::new(e, "vkQueueSubmit"))?;
+
+        Ok(())
+    }
+
+    /// Destroys the views, images, memory, and frame resources.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
+        for &view in &self.views {
+            unsafe {
+                vulkan
+                    .device()
+                    .destroy_image_view(view, VK_GLOBAL_ALLOCATOR.as_deref())
+            };
+        }
+
+        for &image in &self.images {
+            unsafe {
+                vulkan
+                    .device()
+                    .destroy_image(image, VK_GLOBAL_ALLOCATOR.as_deref())
+            };
+        }
+
+        for &memory in &self.memories {
+            unsafe {
+                vulkan
+                    .device()
+                    .free_memory(memory, VK_GLOBAL_ALLOCATOR.as_deref())
+            };
+        }
+
+        for resource in &self.resources {
+            unsafe { resource.destroy(vulkan) };
+        }
+    }
+
+    /// Creates the image, view, and resources of a frame.
+    unsafe fn create_frame<Vulkan: VulkanContext>(
+        &mut self,
+        vulkan: &Vulkan,
+        index: usize,
+        usage: vk::ImageUsageFlags,
+        label: &str,
+    ) -> Result<(), AllocationError> {
+        let format = self.info.format.format;
+
+        let (image, memory, _) = {
+            let create_info = vk::ImageCreateInfo::default()
+                .image_type(vk::ImageType::TYPE_2D)
+                .format(format)
+                .extent(vk::Extent3D {
+                    width: self.info.extent.width,
+                    height: self.info.extent.height,
+                    depth: 1,
+                })
+                .mip_levels(1)
+                .array_layers(1)
+                .samples(vk::SampleCountFlags::TYPE_1)
+                .tiling(vk::ImageTiling::OPTIMAL)
+                .usage(
+                    vk::ImageUsageFlags::COLOR_ATTACHMENT
+                        | vk::ImageUsageFlags::TRANSFER_SRC
+                        | usage,
+                )
+                .sharing_mode(vk::SharingMode::EXCLUSIVE)
+                .initial_layout(vk::ImageLayout::UNDEFINED);
+
+            unsafe {
+                allocate_image(
+                    vulkan,
+                    &create_info,
+                    vk::MemoryPropertyFlags::DEVICE_LOCAL,
+                    &format!("{label} Offscreen {index}"),
+                )
+            }?
+        };
+        self.images.push(image);
+        self.memories.push(memory);
+
+        let view = {
+            let create_info = vk::ImageViewCreateInfo::default()
+                .image(image)
+                .view_type(vk::ImageViewType::TYPE_2D)
+                .format(format)
+                .subresource_range(
+                    vk::ImageSubresourceRange::default()
+                        .aspect_mask(vk::ImageAspectFlags::COLOR)
+                        .base_mip_level(0)
+                        .level_count(1)
+                        .base_array_layer(0)
+                        .layer_count(1),
+                );
+
+            let view = unsafe {
+                vulkan
+                    .device()
+                    .create_image_view(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+            }
+            .map_err(|e| VkError::new(e, "vkCreateImageView"))?;
+
+            unsafe {
+                try_name(
+                    vulkan,
+                    view,
+                    &format!("{label} Offscreen Image View {index}"),
+                )
+            };
+
+            view
+        };
+        self.views.push(view);
+
+        let resources = unsafe { FrameResources::new(vulkan, index) }?;
+        self.resources.push(resources);
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for OffscreenTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OffscreenTarget")
+            .field("info", &self.info)
+            .finish_non_exhaustive()
+    }
+}
@@ -19,6 +19,10 @@ pub struct SwapchainPreferences {
 
     /// The preferred composite alpha.
     pub composite_alpha: Option<Vec<vk::CompositeAlphaFlagsKHR>>,
+
+    /// If the swapchain images' memory should only be allocated when first acquired, requires
+    /// `VK_EXT_swapchain_maintenance1`.
+    pub deferred_memory_allocation: bool,
 }
 
 impl SwapchainPreferences {
@@ -52,6 +56,13 @@ impl SwapchainPreferences {
         self
     }
 
+    /// Sets if the swapchain images' memory should only be allocated when first acquired, requires
+    /// `VK_EXT_swapchain_maintenance1`.
+    pub fn deferred_memory_allocation(mut self, deferred_memory_allocation: bool) -> Self {
+        self.deferred_memory_allocation = deferred_memory_allocation;
+        self
+    }
+
     /// Populates a swapchain create info based on preferences, device capabilities, and reasonable
     /// defaults.
     ///
@@ -60,23 +71,29 @@ impl SwapchainPreferences {
     /// * `image_color_space`
     /// * `image_format`
     /// * `composite_alpha`
-    /// * `present_mode`
+    /// * `present_mode`, see [`select_present_mode`]
+    ///
+    /// ## From preferences:
+    /// * `flags`, `DEFERRED_MEMORY_ALLOCATION_EXT` if `deferred_memory_allocation`
     ///
     /// ## Reasonable defaults:
     /// Field                | Value
     /// ---------------------|------
     /// `surface`            | `surface.surface()`
-    /// `image_extent`       | `capabilities.current_extent`
+    /// `image_extent`       | `capabilities.current_extent`, see [`clamp_extent`]
     /// `pre_transform`      | `capabilities.current_transform`
     /// `image_usage`        | `vk::ImageUsageFlags::COLOR_ATTACHMENT`
     /// `image_sharing_mode` | `vk::SharingMode::EXCLUSIVE`
     /// `clipped`            | `true`
     /// `image_array_layers` | `1`
+    ///
+    /// Returns `None` if the surface has a zero extent, e.g., the window is minimized. Returns
+    /// `ERROR_FORMAT_NOT_SUPPORTED` if the surface reports no formats.
     pub fn get_swapchain_create_info<Vulkan, Surface>(
         &self,
         vulkan: &Vulkan,
         surface: &Surface,
-    ) -> LabelledVkResult<vk::SwapchainCreateInfoKHR<'_>>
+    ) -> LabelledVkResult<Option<vk::SwapchainCreateInfoKHR<'_>>>
     where
         Vulkan: VulkanContext,
         Surface: SurfaceContext,
@@ -92,6 +109,11 @@ impl SwapchainPreferences {
                 .map_err(|e| VkError::new(e, "vkGetPhysicalDeviceSurfaceCapabilitiesKHR"))?
         };
 
+        // Get the extent
+        let Some(extent) = clamp_extent(capabilities.current_extent, &capabilities) else {
+            return Ok(None);
+        };
+
         // Select surface format
         let surface_format = unsafe {
             surface
@@ -124,7 +146,12 @@ impl SwapchainPreferences {
                 None => usize::MAX,
             }
         })
-        .unwrap();
+        .ok_or_else(|| {
+            VkError::new(
+                vk::Result::ERROR_FORMAT_NOT_SUPPORTED,
+                "vkGetPhysicalDeviceSurfaceFormatsKHR",
+            )
+        })?;
 
         // Select the present mode
         let present_mode = unsafe {
@@ -136,15 +163,7 @@ impl SwapchainPreferences {
                 )
                 .map_err(|e| VkError::new(e, "vkGetPhysicalDeviceSurfacePresentModesKHR"))?;
 
-            match self.present_mode.as_ref() {
-                Some(preferences) => preferences
-                    .iter()
-                    .find(|&&preference| supported_present_modes.contains(&preference))
-                    .copied()
-                    .unwrap_or(vk::PresentModeKHR::FIFO),
-
-                None => vk::PresentModeKHR::FIFO,
-            }
+            select_present_mode(self.present_mode.as_deref(), &supported_present_modes)
         };
 
         // Select the composite alpha
@@ -189,14 +208,22 @@ impl SwapchainPreferences {
                 .clamp(capabilities.min_image_count, max_image_count)
         };
 
+        // Get the flags
+        let flags = if self.deferred_memory_allocation {
+            vk::SwapchainCreateFlagsKHR::DEFERRED_MEMORY_ALLOCATION_EXT
+        } else {
+            vk::SwapchainCreateFlagsKHR::empty()
+        };
+
         // Create swapchain info
         let create_info = vk::SwapchainCreateInfoKHR::default()
+            .flags(flags)
             .min_image_count(image_count)
             .image_color_space(surface_format.color_space)
             .image_format(surface_format.format)
             .composite_alpha(composite_alpha)
             .present_mode(present_mode)
-            .image_extent(capabilities.current_extent)
+            .image_extent(extent)
             .pre_transform(capabilities.current_transform)
             .surface(unsafe { surface.surface() })
             .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT)
@@ -204,6 +231,46 @@ impl SwapchainPreferences {
             .clipped(true)
             .image_array_layers(1);
 
-        Ok(create_info)
+        Ok(Some(create_info))
     }
 }
+
+/// Selects the first present mode in `preferences` that is in `supported_present_modes`, falling
+/// back to `vk::PresentModeKHR::FIFO` as it is the only present mode guaranteed to be supported.
+pub fn select_present_mode(
+    preferences: Option<&[vk::PresentModeKHR]>,
+    supported_present_modes: &[vk::PresentModeKHR],
+) -> vk::PresentModeKHR {
+    preferences
+        .unwrap_or_default()
+        .iter()
+        .find(|&preference| supported_present_modes.contains(preference))
+        .copied()
+        .unwrap_or(vk::PresentModeKHR::FIFO)
+}
+
+/// Clamps each dimension of `extent` to the surface's minimum and maximum image extent. Returns
+/// `None` if either dimension is zero after clamping, as a swapchain cannot have a zero extent.
+///
+/// Some drivers transiently report a minimum greater than the maximum, the maximum is then used.
+pub fn clamp_extent(
+    extent: vk::Extent2D,
+    capabilities: &vk::SurfaceCapabilitiesKHR,
+) -> Option<vk::Extent2D> {
+    let extent = vk::Extent2D {
+        width: extent
+            .width
+            .max(capabilities.min_image_extent.width)
+            .min(capabilities.max_image_extent.width),
+        height: extent
+            .height
+            .max(capabilities.min_image_extent.height)
+            .min(capabilities.max_image_extent.height),
+    };
+
+    if extent.width == 0 || extent.height == 0 {
+        return None;
+    }
+
+    Some(extent)
+}
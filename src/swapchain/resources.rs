@@ -1,6 +1,9 @@
 use ash::vk;
 
-use crate::{LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, try_name};
+use crate::{
+    LabelledVkResult, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, cmd_try_begin_label,
+    cmd_try_end_label, record_command_buffer, try_name,
+};
 
 /// The resources for rendering and presenting an individual frame.
 #[derive(Clone, Copy)]
@@ -15,6 +18,8 @@ pub struct FrameResources {
     pub command_pool: vk::CommandPool,
     /// That command pool's command buffer.
     pub command_buffer: vk::CommandBuffer,
+    /// The index of this frame.
+    pub index: usize,
 }
 
 impl FrameResources {
@@ -115,9 +120,48 @@ impl FrameResources {
             render_fence: fence,
             command_pool,
             command_buffer,
+            index,
         })
     }
 
+    /// Resets the command pool, then records `cmd_fn` into the command buffer for a single
+    /// submission, wrapped in a label named after the frame index.
+    ///
+    /// # Safety
+    /// * The command buffer **MUST NOT** be pending execution, i.e., `render_fence` **MUST** be
+    ///   signalled.
+    pub unsafe fn record<Vulkan, CmdFn>(
+        &self,
+        vulkan: &Vulkan,
+        cmd_fn: CmdFn,
+    ) -> LabelledVkResult<()>
+    where
+        Vulkan: VulkanContext,
+        CmdFn: FnOnce(&Vulkan, vk::CommandBuffer),
+    {
+        unsafe {
+            vulkan
+                .device()
+                .reset_command_pool(self.command_pool, vk::CommandPoolResetFlags::empty())
+        }
+        .map_err(|e| VkError::new(e, "vkResetCommandPool"))?;
+
+        let cmd_fn = |vulkan: &Vulkan, command_buffer| unsafe {
+            cmd_try_begin_label(vulkan, command_buffer, &format!("Frame {}", self.index));
+            cmd_fn(vulkan, command_buffer);
+            cmd_try_end_label(vulkan, command_buffer);
+        };
+
+        unsafe {
+            record_command_buffer(
+                vulkan,
+                self.command_buffer,
+                vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT,
+                cmd_fn,
+            )
+        }
+    }
+
     /// Destroy the Vulkan resources for this frame.
     pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
         unsafe {
@@ -0,0 +1,75 @@
+use core::mem;
+
+use ash::vk;
+
+use crate::{SurfaceContext, VK_GLOBAL_ALLOCATOR, VulkanContext};
+
+use super::{FrameResources, Swapchain};
+
+/// A swapchain that has been replaced and is pending destruction, see [`Swapchain::into_retired`].
+pub struct RetiredSwapchain {
+    /// The swapchain, it **MUST NOT** be destroyed until its presentations have completed.
+    pub swapchain: vk::SwapchainKHR,
+    /// The swapchain images' views.
+    pub views: Vec<vk::ImageView>,
+    /// The frame resources that were not moved to a new swapchain.
+    pub resources: Vec<FrameResources>,
+    /// The image indices this swapchain has presented that may still be in use.
+    pub presented_images: Vec<u32>,
+}
+
+impl Swapchain {
+    /// Retires the swapchain, after it has been used as the `old_swapchain` for a new swapchain.
+    /// The frame resources moved to the new swapchain by [`Self::new`] are not retained, only the
+    /// swapchain handle and views that are pending destruction.
+    pub fn into_retired(self) -> RetiredSwapchain {
+        RetiredSwapchain {
+            swapchain: self.swapchain,
+            views: self.views,
+            resources: self.resources,
+            presented_images: self.presented_images,
+        }
+    }
+
+    /// Retires the swapchain handle and views after a failed rebuild, leaving this without a
+    /// swapchain and flagged to rebuild. The frame resources are kept for the next rebuild.
+    pub(super) fn retire_in_place(&mut self) -> RetiredSwapchain {
+        self.needs_to_rebuild = true;
+        self.images.clear();
+        self.acquired_images.clear();
+
+        RetiredSwapchain {
+            swapchain: mem::take(&mut self.swapchain),
+            views: mem::take(&mut self.views),
+            resources: vec![],
+            presented_images: mem::take(&mut self.presented_images),
+        }
+    }
+}
+
+impl RetiredSwapchain {
+    /// Destroys the swapchain, its views, and any remaining frame resources.
+    pub unsafe fn destroy<Vulkan: VulkanContext, Surface: SurfaceContext>(
+        &self,
+        vulkan: &Vulkan,
+        surface: &Surface,
+    ) {
+        unsafe {
+            surface
+                .swapchain_device()
+                .destroy_swapchain(self.swapchain, VK_GLOBAL_ALLOCATOR.as_deref())
+        };
+
+        for &image_view in &self.views {
+            unsafe {
+                vulkan
+                    .device()
+                    .destroy_image_view(image_view, VK_GLOBAL_ALLOCATOR.as_deref())
+            };
+        }
+
+        for resource in &self.resources {
+            unsafe { resource.destroy(vulkan) };
+        }
+    }
+}
@@ -1,25 +1,30 @@
 use acquisition::Acquisition;
 use ash::vk;
-use tracing::error;
+use presentation::Presentation;
 
 use crate::{
-    LabelledVkResult, SurfaceContext, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, try_name,
+    LabelledVkResult, SurfaceContext, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, logging::error,
+    try_name,
 };
 
-use super::Swapchain;
+use super::RetiredSwapchain;
 
 pub mod acquisition;
+pub mod presentation;
 mod process;
 
 /// Handles correctly destroying and freeing retired swapchains when their resources are no longer
 /// in use.
 pub struct SwapchainRetirement {
     /// The retired but non-destroyed swapchains.
-    pub retired_swapchains: Vec<Swapchain>,
+    pub retired_swapchains: Vec<RetiredSwapchain>,
 
     /// Acquisitions to track for confirmation when that frame has been presented.
     pub tracked_acquisitions: Vec<Acquisition>,
 
+    /// Presentations to track for confirmation when that image is no longer in use.
+    pub tracked_presentations: Vec<Presentation>,
+
     /// Fences that need to be freed once they have signalled.
     pub garbage_fences: Vec<vk::Fence>,
 
@@ -37,6 +42,7 @@ impl SwapchainRetirement {
         Self {
             retired_swapchains: vec![],
             tracked_acquisitions: vec![],
+            tracked_presentations: vec![],
             garbage_fences: vec![],
             free_fences: vec![],
             fence_count: 0,
@@ -54,14 +60,15 @@ impl SwapchainRetirement {
         Surface: SurfaceContext,
     {
         self.process_acquisitions(vulkan)?;
+        self.process_presentations(vulkan)?;
         self.recycle_garbage(vulkan)?;
         self.destroy_completed_swapchains(vulkan, surface)?;
 
         Ok(())
     }
 
-    /// House a retired swapchain to be destroyed.
-    pub fn house_swapchain(&mut self, swapchain: Swapchain) {
+    /// House a retired swapchain to be destroyed once its presentations have completed.
+    pub fn house_swapchain(&mut self, swapchain: RetiredSwapchain) {
         self.retired_swapchains.push(swapchain);
     }
 
@@ -96,8 +103,43 @@ impl SwapchainRetirement {
         }
     }
 
-    /// Get a free fence else create a new one to use with a `vkAcquireNextImageKHR` operation.
-    /// The fence **MUST** be returned to the retirement via [`Self::track_acquisition`].
+    /// Track a presentation made with a present fence from
+    /// [`Swapchain::queue_present_with_fence`](super::Swapchain::queue_present_with_fence). Once
+    /// the fence signals, the image is known to be free without waiting for a later acquisition.
+    pub fn track_present(
+        &mut self,
+        swapchain: vk::SwapchainKHR,
+        fence: vk::Fence,
+        image_index: u32,
+    ) {
+        // If the image of the swapchain is already tracked, then it should be replaced with this
+        // new presentation and the fence should be marked as garbage.
+        // Else, a new presentation should be tracked.
+        match self
+            .tracked_presentations
+            .iter_mut()
+            .find(|tracked_presentation| {
+                tracked_presentation.swapchain == swapchain
+                    && tracked_presentation.image_index == image_index
+            }) {
+            Some(tracked_presentation) => {
+                self.garbage_fences.push(tracked_presentation.fence);
+                tracked_presentation.fence = fence;
+            }
+            None => {
+                let presentation = Presentation {
+                    swapchain,
+                    fence,
+                    image_index,
+                };
+                self.tracked_presentations.push(presentation);
+            }
+        }
+    }
+
+    /// Get a free fence else create a new one to use with a `vkAcquireNextImageKHR` or
+    /// `vkQueuePresentKHR` operation. The fence **MUST** be returned to the retirement via
+    /// [`Self::track_acquisition`] or [`Self::track_present`].
     pub fn get_fence<Vulkan: VulkanContext>(
         &mut self,
         vulkan: &Vulkan,
@@ -168,6 +210,16 @@ impl SwapchainRetirement {
             });
         self.tracked_acquisitions.clear();
 
+        // Destroy presentation fences
+        self.tracked_presentations
+            .iter()
+            .for_each(|presentation| unsafe {
+                vulkan
+                    .device()
+                    .destroy_fence(presentation.fence, VK_GLOBAL_ALLOCATOR.as_deref())
+            });
+        self.tracked_presentations.clear();
+
         // destroy swapchains
         self.retired_swapchains
             .iter()
@@ -175,3 +227,38 @@ impl SwapchainRetirement {
         self.retired_swapchains.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ash::vk::{self, Handle};
+
+    use super::SwapchainRetirement;
+
+    #[test]
+    fn repeated_presents_stay_bounded() {
+        let mut retirement = SwapchainRetirement::new();
+        let swapchain = vk::SwapchainKHR::from_raw(1);
+        let image_count = 3;
+
+        for frame in 0..30 {
+            let fence = vk::Fence::from_raw(frame + 2);
+            retirement.track_present(swapchain, fence, (frame % image_count) as u32);
+        }
+
+        assert_eq!(retirement.tracked_presentations.len(), image_count as usize);
+        assert_eq!(retirement.garbage_fences.len(), 30 - image_count as usize);
+    }
+
+    #[test]
+    fn presents_to_other_swapchains_are_tracked_separately() {
+        let mut retirement = SwapchainRetirement::new();
+        let old_swapchain = vk::SwapchainKHR::from_raw(1);
+        let new_swapchain = vk::SwapchainKHR::from_raw(2);
+
+        retirement.track_present(old_swapchain, vk::Fence::from_raw(3), 0);
+        retirement.track_present(new_swapchain, vk::Fence::from_raw(4), 0);
+
+        assert_eq!(retirement.tracked_presentations.len(), 2);
+        assert!(retirement.garbage_fences.is_empty());
+    }
+}
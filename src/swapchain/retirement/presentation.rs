@@ -0,0 +1,25 @@
+use core::slice;
+
+use ash::vk;
+
+use crate::{LabelledVkResult, VkError, VulkanContext, fences_are_signaled};
+
+/// A swapchain image presentation with a present fence.
+pub struct Presentation {
+    /// The swapchain that the image was presented to.
+    pub swapchain: vk::SwapchainKHR,
+    /// The present fence tracking the presentation.
+    pub fence: vk::Fence,
+    /// The image index that was presented.
+    pub image_index: u32,
+}
+
+impl Presentation {
+    /// Is the image no longer in use by the presentation engine.
+    pub fn is_complete<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) -> LabelledVkResult<bool> {
+        let is_complete = unsafe { fences_are_signaled(vulkan, slice::from_ref(&self.fence)) }
+            .map_err(|e| VkError::new(e, "vkWaitForFences"))?;
+
+        Ok(is_complete)
+    }
+}
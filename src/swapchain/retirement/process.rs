@@ -110,6 +110,76 @@ impl SwapchainRetirement {
         Ok(())
     }
 
+    /// Processes the tracked presentations to remove the completed presentations' image indices
+    /// from their retired swapchain's present history.
+    ///
+    /// Presentations to a swapchain that has not been retired are kept until it is retired, as
+    /// its present history is only tracked once it is housed. Only the latest presentation of each
+    /// image is tracked, see [`SwapchainRetirement::track_present`].
+    pub(super) fn process_presentations<Vulkan>(&mut self, vulkan: &Vulkan) -> LabelledVkResult<()>
+    where
+        Vulkan: VulkanContext,
+    {
+        // Find and remove the completed presentations
+        let completed_presentations = {
+            let mut completed_presentations = vec![];
+
+            let mut length = self.tracked_presentations.len();
+            let mut index = 0;
+            while index < length {
+                let presentation = &self.tracked_presentations[index];
+                let is_retired = self
+                    .retired_swapchains
+                    .iter()
+                    .any(|swapchain| swapchain.swapchain == presentation.swapchain);
+
+                if is_retired && presentation.is_complete(vulkan)? {
+                    // The current index has been replaced with the last item, thus current index
+                    // should not change.
+                    let presentation = self.tracked_presentations.swap_remove(index);
+                    completed_presentations.push(presentation);
+
+                    // An item has been removed from the vec, thus the length should be decremented.
+                    length -= 1;
+                } else {
+                    // Move to the next item
+                    index += 1;
+                }
+            }
+
+            completed_presentations
+        };
+
+        // Remove the completed presentation's image index from the swapchain it was presented to.
+        for presentation in &completed_presentations {
+            self.retired_swapchains.iter_mut().for_each(|swapchain| {
+                if swapchain.swapchain == presentation.swapchain {
+                    swapchain
+                        .presented_images
+                        .retain(|image| *image != presentation.image_index)
+                }
+            });
+        }
+
+        // Recycle the fences
+        {
+            let mut fences: Vec<_> = completed_presentations
+                .iter()
+                .map(|presentation| presentation.fence)
+                .collect();
+
+            // Reset and recycle the fences
+            if !fences.is_empty() {
+                unsafe { vulkan.device().reset_fences(&fences) }
+                    .map_err(|e| VkError::new(e, "vkResetFences"))?;
+
+                self.free_fences.append(&mut fences);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Destroy the swapchains that have completed their work.
     pub(super) fn destroy_completed_swapchains<Vulkan, Surface>(
         &mut self,
@@ -144,6 +214,19 @@ impl SwapchainRetirement {
             safe_swapchains
         };
 
+        // Stop tracking the remaining presentations to the swapchains, their fences are recycled
+        // once they signal.
+        for swapchain in &safe_swapchains {
+            self.tracked_presentations.retain(|presentation| {
+                if presentation.swapchain == swapchain.swapchain {
+                    self.garbage_fences.push(presentation.fence);
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+
         // Destroy the swapchains
         safe_swapchains
             .iter()
@@ -0,0 +1,135 @@
+use ash::vk;
+
+use crate::{LabelledVkResult, MaybeMutex, SurfaceContext, VulkanContext};
+
+use super::{Frame, OffscreenTarget, Swapchain, SwapchainInfo};
+
+/// A target that frames are acquired from, recorded into, then presented to, so rendering code can
+/// be shared between a [`Swapchain`] and an [`OffscreenTarget`].
+///
+/// `Surface` is the surface context a swapchain presents with, an offscreen target accepts any
+/// context, e.g., `()`.
+pub trait RenderTarget<Surface> {
+    /// The target's info.
+    fn info(&self) -> &SwapchainInfo;
+
+    /// The layout a frame's image **MUST** be transitioned to before it is presented.
+    fn present_layout(&self) -> vk::ImageLayout;
+
+    /// Acquires the next frame, `acquire_fence` signals once the image has been acquired. Returns
+    /// `None` if no frame was acquired.
+    ///
+    /// The frame's `render_fence` has been waited on and reset, it **MUST** be signalled by the
+    /// frame's submission.
+    ///
+    /// # Safety
+    /// * `queue` **MUST** be from the context's queue family.
+    /// * `acquire_fence` **MUST** be null or unsignalled and not associated with any other queue
+    ///   operation.
+    unsafe fn acquire_frame<'m, Vulkan, Queue>(
+        &mut self,
+        vulkan: &Vulkan,
+        surface: &Surface,
+        queue: Queue,
+        acquire_fence: vk::Fence,
+    ) -> LabelledVkResult<Option<Frame>>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>;
+
+    /// Presents the image of `image_index` once `wait_semaphore` has been signalled.
+    ///
+    /// # Safety
+    /// * `queue` **MUST** be from the context's queue family and, for a swapchain, support
+    ///   presenting to the surface.
+    /// * The image **MUST** be in [`Self::present_layout`] once `wait_semaphore` is signalled.
+    unsafe fn present_frame<'m, Vulkan, Queue>(
+        &mut self,
+        vulkan: &Vulkan,
+        surface: &Surface,
+        image_index: u32,
+        wait_semaphore: vk::Semaphore,
+        queue: Queue,
+    ) -> LabelledVkResult<()>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>;
+}
+
+impl<Surface: SurfaceContext> RenderTarget<Surface> for Swapchain {
+    fn info(&self) -> &SwapchainInfo {
+        &self.info
+    }
+
+    fn present_layout(&self) -> vk::ImageLayout {
+        vk::ImageLayout::PRESENT_SRC_KHR
+    }
+
+    unsafe fn acquire_frame<'m, Vulkan, Queue>(
+        &mut self,
+        vulkan: &Vulkan,
+        surface: &Surface,
+        _queue: Queue,
+        acquire_fence: vk::Fence,
+    ) -> LabelledVkResult<Option<Frame>>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    {
+        self.acquire_next_image(vulkan, surface, acquire_fence)
+    }
+
+    unsafe fn present_frame<'m, Vulkan, Queue>(
+        &mut self,
+        _vulkan: &Vulkan,
+        surface: &Surface,
+        image_index: u32,
+        wait_semaphore: vk::Semaphore,
+        queue: Queue,
+    ) -> LabelledVkResult<()>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    {
+        self.queue_present(surface, image_index, wait_semaphore, queue)
+    }
+}
+
+impl<Surface> RenderTarget<Surface> for OffscreenTarget {
+    fn info(&self) -> &SwapchainInfo {
+        &self.info
+    }
+
+    fn present_layout(&self) -> vk::ImageLayout {
+        self.present_layout
+    }
+
+    unsafe fn acquire_frame<'m, Vulkan, Queue>(
+        &mut self,
+        vulkan: &Vulkan,
+        _surface: &Surface,
+        queue: Queue,
+        acquire_fence: vk::Fence,
+    ) -> LabelledVkResult<Option<Frame>>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    {
+        unsafe { self.acquire(vulkan, queue, acquire_fence) }.map(Some)
+    }
+
+    unsafe fn present_frame<'m, Vulkan, Queue>(
+        &mut self,
+        vulkan: &Vulkan,
+        _surface: &Surface,
+        image_index: u32,
+        wait_semaphore: vk::Semaphore,
+        queue: Queue,
+    ) -> LabelledVkResult<()>
+    where
+        Vulkan: VulkanContext,
+        Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    {
+        unsafe { self.queue_present(vulkan, image_index, wait_semaphore, queue) }
+    }
+}
@@ -0,0 +1,23 @@
+use ash::vk;
+
+use crate::{LabelledVkResult, MaybeMutex, VkError, VulkanContext};
+
+/// Waits for each of `queues` to be idle in turn. Unlike `vkDeviceWaitIdle`, other queues on the
+/// device are not stalled.
+pub unsafe fn wait_queues_idle<'m, Vulkan, Queue>(
+    vulkan: &Vulkan,
+    queues: &[Queue],
+) -> LabelledVkResult<()>
+where
+    Vulkan: VulkanContext,
+    Queue: Into<MaybeMutex<'m, vk::Queue>> + Copy,
+{
+    for &queue in queues {
+        let (queue, _queue_guard) = queue.into().lock();
+
+        unsafe { vulkan.device().queue_wait_idle(queue) }
+            .map_err(|e| VkError::new(e, "vkQueueWaitIdle"))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use ash::vk;
+
+use crate::{
+    AllocationError, MaybeMutex, VK_GLOBAL_ALLOCATOR, VkError, VulkanContext, try_name,
+    upload_image,
+};
+
+/// A sampled image, its memory, and its view.
+#[derive(Debug, Clone, Copy)]
+pub struct Texture {
+    /// The image.
+    pub image: vk::Image,
+    /// The image's memory.
+    pub memory: vk::DeviceMemory,
+    /// The image's view.
+    pub view: vk::ImageView,
+}
+
+impl Texture {
+    /// Destroys the view and image, and frees the memory.
+    pub unsafe fn destroy<Vulkan: VulkanContext>(&self, vulkan: &Vulkan) {
+        unsafe {
+            vulkan
+                .device()
+                .destroy_image_view(self.view, VK_GLOBAL_ALLOCATOR.as_deref());
+            vulkan
+                .device()
+                .destroy_image(self.image, VK_GLOBAL_ALLOCATOR.as_deref());
+            vulkan
+                .device()
+                .free_memory(self.memory, VK_GLOBAL_ALLOCATOR.as_deref());
+        }
+    }
+}
+
+/// Creates a 1x1 `R8G8B8A8_UNORM` texture of `colour` in `SHADER_READ_ONLY_OPTIMAL`, for use in
+/// place of a missing texture.
+pub unsafe fn create_placeholder_texture<'m, Vulkan, Queue, Pool>(
+    vulkan: &Vulkan,
+    command_pool: Pool,
+    queue: Queue,
+    colour: [u8; 4],
+    label: &str,
+) -> Result<Texture, AllocationError>
+where
+    Vulkan: VulkanContext,
+    Queue: Into<MaybeMutex<'m, vk::Queue>>,
+    Pool: Into<MaybeMutex<'m, vk::CommandPool>>,
+{
+    let format = vk::Format::R8G8B8A8_UNORM;
+
+    let (image, memory, _) = {
+        let create_info = vk::ImageCreateInfo::default()
+            .image_type(vk::ImageType::TYPE_2D)
+            .format(format)
+            .extent(vk::Extent3D {
+                width: 1,
+                height: 1,
+                depth: 1,
+            })
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(vk::SampleCountFlags::TYPE_1)
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::TRANSFER_DST)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+
+        unsafe {
+            upload_image(
+                vulkan,
+                command_pool,
+                queue,
+                &create_info,
+                &colour,
+                vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                label,
+            )
+        }?
+    };
+
+    let view = {
+        let create_info = vk::ImageViewCreateInfo::default()
+            .image(image)
+            .view_type(vk::ImageViewType::TYPE_2D)
+            .format(format)
+            .subresource_range(
+                vk::ImageSubresourceRange::default()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .base_mip_level(0)
+                    .level_count(1)
+                    .base_array_layer(0)
+                    .layer_count(1),
+            );
+
+        let view = match unsafe {
+            vulkan
+                .device()
+                .create_image_view(&create_info, VK_GLOBAL_ALLOCATOR.as_deref())
+        } {
+            Ok(view) => view,
+            Err(e) => {
+                unsafe {
+                    vulkan
+                        .device()
+                        .destroy_image(image, VK_GLOBAL_ALLOCATOR.as_deref());
+                    vulkan
+                        .device()
+                        .free_memory(memory, VK_GLOBAL_ALLOCATOR.as_deref());
+                }
+
+                return Err(VkError::new(e, "vkCreateImageView").into());
+            }
+        };
+
+        unsafe { try_name(vulkan, view, &format!("{label} Image View")) };
+
+        view
+    };
+
+    Ok(Texture {
+        image,
+        memory,
+        view,
+    })
+}